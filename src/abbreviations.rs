@@ -0,0 +1,88 @@
+use std::{borrow::Cow, collections::HashMap};
+
+use crate::{
+    autolinks::is_url, directives::lines_outside_raw_bodies, extensions::HtmlWriting,
+    parser::Context, warn,
+};
+
+/// Parses an abbreviation definition line, `*[HTML]: HyperText Markup Language`
+pub fn parse_abbreviation_definition(line: &str) -> Option<(&str, &str)> {
+    let (abbreviation, title) = line.strip_prefix("*[")?.split_once("]:")?;
+    let title = title.trim();
+    if abbreviation.trim().is_empty() || abbreviation.contains(']') || title.is_empty() {
+        return None;
+    }
+    Some((abbreviation.trim(), title))
+}
+
+/// Collects all abbreviation definitions in the document, longest first, so an abbreviation is
+/// never matched as part of a longer one
+pub fn collect_abbreviations(source: &str) -> Vec<(String, String)> {
+    let mut definitions = HashMap::new();
+    for (abbreviation, title) in lines_outside_raw_bodies(source)
+        .filter_map(|line| parse_abbreviation_definition(line.trim()))
+    {
+        if definitions
+            .insert(abbreviation.to_string(), title.to_string())
+            .is_some()
+        {
+            warn(&format!(
+                "Abbreviation {abbreviation} is defined more than once, using the last definition..."
+            ));
+        }
+    }
+    let mut abbreviations = definitions.into_iter().collect::<Vec<_>>();
+    abbreviations.sort_by(|(a, _), (b, _)| b.len().cmp(&a.len()).then(a.cmp(b)));
+    abbreviations
+}
+
+#[inline(always)]
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Wraps every whole-word occurrence of an abbreviation in an `<abbr>` with its definition
+pub fn wrap_abbreviations<'a>(text: &'a str, ctx: &Context) -> Cow<'a, str> {
+    if ctx.abbreviations.is_empty() {
+        return Cow::Borrowed(text);
+    }
+    let mut output = String::new();
+    let mut last_end = 0;
+    let mut idx = 0;
+    while idx < text.len() {
+        let rest = &text[idx..];
+        if !text[..idx].ends_with(|c: char| !c.is_whitespace()) {
+            let word = &rest[..rest.find(char::is_whitespace).unwrap_or(rest.len())];
+            if is_url(word) {
+                idx += word.len();
+                continue;
+            }
+        }
+        let word_start = !text[..idx].chars().next_back().is_some_and(is_word_char);
+        let found = ctx
+            .abbreviations
+            .iter()
+            .filter(|_| word_start)
+            .find(|(abbreviation, _)| {
+                text[idx..].starts_with(abbreviation.as_str())
+                    && !text[idx + abbreviation.len()..]
+                        .chars()
+                        .next()
+                        .is_some_and(is_word_char)
+            });
+        match found {
+            Some((abbreviation, title)) => {
+                output.push_str(&text[last_end..idx]);
+                output.write_tag("abbr", abbreviation, &[("title", title)]);
+                idx += abbreviation.len();
+                last_end = idx;
+            }
+            None => idx += text[idx..].chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    if last_end == 0 {
+        return Cow::Borrowed(text);
+    }
+    output.push_str(&text[last_end..]);
+    Cow::Owned(output)
+}
@@ -0,0 +1,43 @@
+use std::io::{BufReader, Read};
+
+use crate::{
+    attributes::unqoute,
+    directives::{directive_options, read_block_body, warn_unknown_option},
+    extensions::HtmlWriting,
+    parser::{Context, parse_nested, parse_text},
+};
+
+/// Parses the options of an admonition, i.e. `title="Heads up"`, returning its title
+pub fn parse_admonition_options<'a>(kind: &str, info: &'a str) -> Option<&'a str> {
+    let mut title = None;
+    for token in directive_options(kind, info) {
+        match token.split_once('=') {
+            Some(("title", value)) => title = Some(unqoute(value)),
+            _ => warn_unknown_option(kind, token),
+        }
+    }
+    title
+}
+
+/// Renders a note, tip, warning or danger block, whose body is parsed as FXG
+pub fn parse_admonition<T>(
+    reader: &mut BufReader<T>,
+    output: &mut String,
+    kind: &str,
+    title: Option<&str>,
+    ctx: &mut Context,
+) where
+    T: Read,
+{
+    let body = read_block_body(reader);
+    output.write_opening_tag("aside", &[("class", &format!("fxg-{kind}"))]);
+    if let Some(title) = title {
+        output.write_tag(
+            "p",
+            &parse_text(title, ctx),
+            &[("class", "fxg-admonition-title")],
+        );
+    }
+    output.push_str(&parse_nested(&body, ctx));
+    output.write_closing_tag("aside");
+}
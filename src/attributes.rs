@@ -0,0 +1,140 @@
+use crate::parser::Context;
+
+/// Splits on whitespace, except for whitespace inside of double qoutes. Returns `None` if a
+/// qoute is never closed.
+pub fn split_tokens(info: &str) -> Option<Vec<&str>> {
+    let mut tokens = vec![];
+    let mut in_qoutes = false;
+    let mut token_start = None;
+    for (idx, c) in info.char_indices() {
+        if c == '"' {
+            in_qoutes = !in_qoutes;
+        }
+        if c.is_whitespace() && !in_qoutes {
+            if let Some(start) = token_start.take() {
+                tokens.push(&info[start..idx]);
+            }
+        } else if token_start.is_none() {
+            token_start = Some(idx);
+        }
+    }
+    if in_qoutes {
+        return None;
+    }
+    if let Some(start) = token_start {
+        tokens.push(&info[start..]);
+    }
+    Some(tokens)
+}
+
+#[inline(always)]
+pub fn unqoute(value: &str) -> &str {
+    value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .unwrap_or(value)
+}
+
+/// Whether an attribute can be given to any element that takes an attribute block, which are
+/// IDs, classes and `data-` attributes
+#[inline(always)]
+pub fn is_generic_attribute(key: &str) -> bool {
+    matches!(key, "id" | "class") || key.starts_with("data-")
+}
+
+/// Reserves the ID among the attributes of an element, like [`Context::explicit_id`] does for
+/// headings. Returns the ID that was reserved, which is renamed if it was already taken.
+pub fn reserve_id(attributes: &[(&str, &str)], ctx: &Context) -> Option<String> {
+    attributes
+        .iter()
+        .find(|(key, _)| *key == "id")
+        .map(|(_, id)| ctx.explicit_id(id))
+}
+
+/// Replaces the ID among the attributes of an element with the one [`reserve_id`] returned
+pub fn replace_id<'a>(
+    attributes: &[(&'a str, &'a str)],
+    id: Option<&'a str>,
+) -> Vec<(&'a str, &'a str)> {
+    attributes
+        .iter()
+        .map(|&(key, value)| match (key, id) {
+            ("id", Some(id)) => (key, id),
+            _ => (key, value),
+        })
+        .collect()
+}
+
+/// Parses a single attribute, which is either `#id`, `.class` or `key=value`
+pub fn parse_attribute(token: &str) -> Option<(&str, &str)> {
+    if let Some(id) = token.strip_prefix('#') {
+        return (!id.is_empty() && !id.contains('"')).then_some(("id", id));
+    }
+    if let Some(class) = token.strip_prefix('.') {
+        return (!class.is_empty() && !class.contains('"')).then_some(("class", class));
+    }
+    let (key, value) = token.split_once('=')?;
+    if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return None;
+    }
+    Some((key, unqoute(value)))
+}
+
+/// Splits an attribute block like `{title="Some title" target=_blank}` or `{#id .class}` off of
+/// the start of `text`. Returns the attributes and the text after the block.
+pub fn split_attribute_block(text: &str) -> Option<(Vec<(&str, &str)>, &str)> {
+    let block = text.strip_prefix('{')?;
+    let mut in_qoutes = false;
+    let end = block.find(|c: char| {
+        if c == '"' {
+            in_qoutes = !in_qoutes;
+        }
+        c == '}' && !in_qoutes
+    })?;
+    let mut attributes = vec![];
+    for token in split_tokens(&block[..end])? {
+        attributes.push(parse_attribute(token)?);
+    }
+    if attributes.is_empty() {
+        return None;
+    }
+    Some((attributes, &block[end + 1..]))
+}
+
+/// Splits an attribute block that contains only generic attributes off of the start of `text`
+pub fn split_generic_attributes(text: &str) -> Option<(Vec<(&str, &str)>, &str)> {
+    split_attribute_block(text)
+        .filter(|(attributes, _)| attributes.iter().all(|(key, _)| is_generic_attribute(key)))
+}
+
+/// A span of text with attributes, `[text]{#id .class}`
+pub struct Span<'a> {
+    pub start: usize,
+    pub contents: &'a str,
+    pub attributes: Vec<(&'a str, &'a str)>,
+    /// Index of the first character after the attribute block
+    pub end: usize,
+}
+
+/// Finds the first span in a line. Brackets that aren't directly followed by an attribute block
+/// are not a span.
+pub fn find_span(line: &str) -> Option<Span<'_>> {
+    if !line.contains("]{") {
+        return None;
+    }
+    line.match_indices('[').find_map(|(start, _)| {
+        line[start + 1..]
+            .match_indices("]{")
+            .find_map(|(contents_end, _)| {
+                let contents_end = start + 1 + contents_end;
+                let block = &line[contents_end + 1..];
+                let (attributes, rest) = split_generic_attributes(block)?;
+                Some(Span {
+                    start,
+                    contents: &line[start + 1..contents_end],
+                    attributes,
+                    end: line.len() - rest.len(),
+                })
+            })
+    })
+}
@@ -0,0 +1,108 @@
+/// A bare URL or email address in plain text
+#[derive(Debug)]
+pub struct Autolink {
+    pub start: usize,
+    pub end: usize,
+    pub href: String,
+}
+
+/// Punctuation at the end of a URL is most likely part of the sentence around it
+const TRAILING_PUNCTUATION: &[char] = &['.', ',', ':', ';', '!', '?', '\'', '"'];
+
+/// Whether a word of plain text is a URL, which is left as it is by passes that change text
+pub fn is_url(word: &str) -> bool {
+    word.contains("://") || word.starts_with("www.") || word.starts_with("mailto:")
+}
+
+#[inline(always)]
+fn is_word_start(line: &str, idx: usize) -> bool {
+    !line[..idx]
+        .chars()
+        .next_back()
+        .is_some_and(|c| c.is_alphanumeric() || c == '.' || c == '/')
+}
+
+#[inline(always)]
+fn is_email_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || ['.', '_', '%', '+', '-'].contains(&c)
+}
+
+/// Finds the end of a URL starting at `start`, without trailing punctuation or unbalanced
+/// closing parentheses
+pub fn url_end(line: &str, start: usize) -> usize {
+    let mut url = &line[start..];
+    url = &url[..url
+        .find(|c: char| c.is_whitespace() || c == '<' || c == '>')
+        .unwrap_or(url.len())];
+    loop {
+        if let Some(trimmed) = url.strip_suffix(TRAILING_PUNCTUATION) {
+            url = trimmed;
+        } else if url.ends_with(')') && url.matches(')').count() > url.matches('(').count() {
+            url = &url[..url.len() - 1];
+        } else {
+            break;
+        }
+    }
+    start + url.len()
+}
+
+fn find_url(line: &str) -> Option<Autolink> {
+    ["https://", "http://", "www."]
+        .iter()
+        .filter_map(|prefix| {
+            line.match_indices(prefix)
+                .map(|(start, _)| start)
+                .find(|start| is_word_start(line, *start))
+                .map(|start| (prefix, start))
+        })
+        .filter_map(|(prefix, start)| {
+            let end = url_end(line, start);
+            // a prefix on its own is not a link
+            if end <= start + prefix.len() {
+                return None;
+            }
+            let text = &line[start..end];
+            let href = if *prefix == "www." {
+                format!("https://{text}")
+            } else {
+                text.to_string()
+            };
+            Some(Autolink { start, end, href })
+        })
+        .min_by_key(|autolink| autolink.start)
+}
+
+fn find_email(line: &str) -> Option<Autolink> {
+    for (at, _) in line.match_indices('@') {
+        let Some((start, _)) = line[..at]
+            .char_indices()
+            .rev()
+            .take_while(|(_, c)| is_email_char(*c))
+            .last()
+        else {
+            continue;
+        };
+        let domain_len = line[at + 1..]
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '.' || c == '-'))
+            .unwrap_or(line.len() - at - 1);
+        let domain = line[at + 1..at + 1 + domain_len].trim_end_matches(['.', '-']);
+        if !domain.contains('.') || domain.starts_with('.') || !is_word_start(line, start) {
+            continue;
+        }
+        let end = at + 1 + domain.len();
+        return Some(Autolink {
+            start,
+            end,
+            href: format!("mailto:{}", &line[start..end]),
+        });
+    }
+    None
+}
+
+/// Finds the first bare URL, `www.` host or email address in `line`
+pub fn find_autolink(line: &str) -> Option<Autolink> {
+    [find_url(line), find_email(line)]
+        .into_iter()
+        .flatten()
+        .min_by_key(|autolink| autolink.start)
+}
@@ -1,17 +1,28 @@
 use std::io::{BufRead, BufReader, Read};
 
-use crate::{extensions::HtmlWriting, parser::parse_text};
+use crate::{
+    extensions::HtmlWriting,
+    parser::{Context, parse_text},
+};
 
 struct QouteData<'lines>(isize, bool, &'lines str);
 
-pub fn parse_blockqoute<T>(reader: &mut BufReader<T>, line: String) -> (String, String)
+pub fn parse_blockqoute<T>(
+    reader: &mut BufReader<T>,
+    line: String,
+    ctx: &Context,
+) -> (String, String)
 where
     T: Read,
 {
-    parse_blockqoute_internal(reader, line)
+    parse_blockqoute_internal(reader, line, ctx)
 }
 
-fn parse_blockqoute_internal<T>(reader: &mut BufReader<T>, line: String) -> (String, String)
+fn parse_blockqoute_internal<T>(
+    reader: &mut BufReader<T>,
+    line: String,
+    ctx: &Context,
+) -> (String, String)
 where
     T: Read,
 {
@@ -21,7 +32,7 @@ where
         if len == 0 {
             break;
         }
-        if line.starts_with(&['>', '-']) {
+        if line.starts_with(['>', '-']) {
             qoute.push_str(&line);
         } else {
             break;
@@ -71,7 +82,7 @@ where
                 current_depth = data.0;
             }
         }
-        output.push_str(&parse_text(data.2));
+        output.push_str(&parse_text(data.2, ctx));
         output.write_opening_tag("br", &[]);
     }
 
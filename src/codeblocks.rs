@@ -0,0 +1,297 @@
+use std::{
+    fs,
+    io::{BufRead, BufReader, Read},
+};
+
+use crate::{
+    attributes::{
+        is_generic_attribute, parse_attribute, replace_id, reserve_id, split_tokens, unqoute,
+    },
+    directives::is_directive,
+    extensions::HtmlWriting,
+    parser::Context,
+    warn,
+};
+
+/// Everything that can be passed to a codeblock opener, e.g.
+/// `<rs title=src/main.rs lines=3-5 numbers .example>`
+#[derive(Debug, Default)]
+pub struct CodeblockOptions<'a> {
+    lang: &'a str,
+    title: Option<&'a str>,
+    highlighted: Vec<(usize, usize)>,
+    numbers: bool,
+    include: Option<&'a str>,
+    ranges: Vec<(usize, usize)>,
+    region: Option<&'a str>,
+    /// IDs, classes and `data-` attributes of the `<pre>`
+    attributes: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> CodeblockOptions<'a> {
+    /// Parses the text between the `<` and `>` of a codeblock opener. Returns `None` if the line
+    /// is not a codeblock opener at all, which includes openers of directives like `<note>`.
+    pub fn parse(info: &'a str) -> Option<Self> {
+        let mut tokens = split_tokens(info)?.into_iter();
+        let lang = tokens.next().unwrap_or("");
+        if !lang.chars().all(char::is_alphabetic) || is_directive(lang) {
+            return None;
+        }
+
+        let mut options = Self {
+            lang,
+            ..Default::default()
+        };
+        for token in tokens {
+            match token.split_once('=') {
+                Some(("title", title)) => options.title = Some(unqoute(title)),
+                Some(("lines", ranges)) => options.highlighted = parse_line_ranges(ranges),
+                Some(("include", path)) => options.include = Some(unqoute(path)),
+                Some(("range", ranges)) => options.ranges = parse_line_ranges(ranges),
+                Some(("region", region)) => options.region = Some(unqoute(region)),
+                None if token == "numbers" => options.numbers = true,
+                _ => match parse_attribute(token) {
+                    Some(attribute) if is_generic_attribute(attribute.0) => {
+                        options.attributes.push(attribute)
+                    }
+                    _ => return None,
+                },
+            }
+        }
+        Some(options)
+    }
+
+    /// Whether the codeblock includes a file, in which case it has no body
+    pub fn is_include(&self) -> bool {
+        self.include.is_some()
+    }
+
+    fn is_highlighted(&self, line: usize) -> bool {
+        self.highlighted
+            .iter()
+            .any(|(start, end)| (*start..=*end).contains(&line))
+    }
+}
+
+/// Parses line ranges like `3`, `3-5` or `1,3-5,8`
+fn parse_line_ranges(ranges: &str) -> Vec<(usize, usize)> {
+    let mut output = vec![];
+    for range in unqoute(ranges).split(',') {
+        let parsed = match range.split_once('-') {
+            Some((start, end)) => start.trim().parse().ok().zip(end.trim().parse().ok()),
+            None => range.trim().parse().ok().map(|line| (line, line)),
+        };
+        match parsed {
+            Some((start, end)) if start <= end => output.push((start, end)),
+            _ => warn(&format!(
+                "Line range {range} in codeblock opener is not valid, ignoring..."
+            )),
+        }
+    }
+    output
+}
+
+pub fn parse_codeblock<T>(
+    reader: &mut BufReader<T>,
+    output: &mut String,
+    mut lnbuf: String,
+    options: &CodeblockOptions,
+    ctx: &mut Context,
+) where
+    T: Read,
+{
+    if let Some(path) = options.include {
+        // included codeblocks don't have a body, so there is no closing tag to look for
+        if ctx.options.safe {
+            warn(&format!(
+                "Including {path} is not allowed in safe mode, leaving it out..."
+            ));
+            return;
+        }
+        if let Some(lines) = read_include(path, options, ctx) {
+            let lines = lines
+                .iter()
+                .map(|(n, line)| (*n, line.as_str()))
+                .collect::<Vec<_>>();
+            write_codeblock(output, &lines, options, ctx);
+        }
+        return;
+    }
+
+    lnbuf.clear();
+    while let Ok(length) = reader.read_line(&mut lnbuf) {
+        if length == 0 {
+            break;
+        }
+        if &lnbuf[lnbuf.len() - length..lnbuf.len() - 1] == "</>" {
+            break;
+        }
+    }
+
+    let mut lines = lnbuf
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| (idx + 1, line))
+        .collect::<Vec<_>>();
+    // the last line is the closing tag
+    lines.pop();
+    write_codeblock(output, &lines, options, ctx);
+}
+
+/// Reads the lines of an included file, limited to `ranges` or `region` if they are given
+fn read_include(
+    path: &str,
+    options: &CodeblockOptions,
+    ctx: &Context,
+) -> Option<Vec<(usize, String)>> {
+    let full_path = ctx.options.base_dir.join(path);
+    let contents = match fs::read_to_string(&full_path) {
+        Ok(contents) => contents,
+        Err(e) => {
+            warn(&format!(
+                "Included file {} wasn't able to be read ({e})",
+                full_path.display()
+            ));
+            return None;
+        }
+    };
+    let lines = contents
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| (idx + 1, line));
+
+    if let Some(region) = options.region {
+        if !options.ranges.is_empty() {
+            warn(&format!(
+                "Include of {path} has both a range and a region, ignoring the range..."
+            ));
+        }
+        let start_marker = format!("fxg-region: {region}");
+        let end_marker = format!("fxg-endregion: {region}");
+        let mut in_region = false;
+        let mut found = false;
+        let mut output = vec![];
+        for (number, line) in lines {
+            if line.trim_end().ends_with(&start_marker) {
+                in_region = true;
+                found = true;
+            } else if line.trim_end().ends_with(&end_marker) {
+                in_region = false;
+            } else if in_region && !is_region_marker(line) {
+                output.push((number, line.to_string()));
+            }
+        }
+        if !found {
+            warn(&format!(
+                "Region {region} was not found in included file {path}"
+            ));
+            return None;
+        }
+        if in_region {
+            warn(&format!(
+                "Region {region} is never ended in included file {path}, including the rest of the file..."
+            ));
+        }
+        return Some(output);
+    }
+
+    let line_count = contents.lines().count();
+    for (start, end) in options
+        .ranges
+        .iter()
+        .filter(|(start, _)| *start > line_count)
+    {
+        warn(&format!(
+            "Range {start}-{end} is outside of included file {path}, ignoring it..."
+        ));
+    }
+    let output = lines
+        .filter(|(number, _)| {
+            options.ranges.is_empty()
+                || options
+                    .ranges
+                    .iter()
+                    .any(|(start, end)| (start..=end).contains(&number))
+        })
+        .map(|(number, line)| (number, line.to_string()))
+        .collect::<Vec<_>>();
+    (!output.is_empty()).then_some(output)
+}
+
+#[inline(always)]
+fn is_region_marker(line: &str) -> bool {
+    line.contains("fxg-region: ") || line.contains("fxg-endregion: ")
+}
+
+/// Writes a codeblock with its lines dedented. Lines are numbered, so included code can keep the
+/// line numbers of the file it was taken from.
+fn write_codeblock(
+    output: &mut String,
+    lines: &[(usize, &str)],
+    options: &CodeblockOptions,
+    ctx: &mut Context,
+) {
+    ctx.codeblocks += 1;
+    if let Some(title) = options.title {
+        output.write_opening_tag("figure", &[("class", "fxg-codeblock")]);
+        output.write_tag("figcaption", title, &[]);
+    }
+    let id = reserve_id(&options.attributes, ctx);
+    output.write_opening_tag("pre", &replace_id(&options.attributes, id.as_deref()));
+    if options.lang.is_empty() {
+        output.write_opening_tag("code", &[]);
+    } else {
+        output.write_opening_tag("code", &[("class", &format!("language-{}", options.lang))]);
+    }
+
+    // indentation is counted in characters, as it can contain multibyte whitespace
+    let least_indent = lines
+        .iter()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(_, line)| line.chars().take_while(|c| c.is_whitespace()).count())
+        .min()
+        .unwrap_or(0);
+
+    for (line_number, line) in lines {
+        let line = if line.trim().is_empty() {
+            line.trim()
+        } else {
+            let indent = line
+                .char_indices()
+                .nth(least_indent)
+                .map_or(line.len(), |(idx, _)| idx);
+            &line[indent..]
+        };
+        if !options.numbers && options.highlighted.is_empty() {
+            output.push_str(line);
+            output.push('\n');
+            continue;
+        }
+
+        // highlighted lines are <mark>ed, the rest is wrapped in a <span> so all lines can be styled
+        let tag = if options.is_highlighted(*line_number) {
+            "mark"
+        } else {
+            "span"
+        };
+        if options.numbers {
+            let id = format!("fxg-code{}-L{line_number}", ctx.codeblocks);
+            output.write_opening_tag(tag, &[("class", "fxg-line"), ("id", &id)]);
+            output.write_tag(
+                "a",
+                &line_number.to_string(),
+                &[("class", "fxg-lineno"), ("href", &format!("#{id}"))],
+            );
+        } else {
+            output.write_opening_tag(tag, &[("class", "fxg-line")]);
+        }
+        output.push_str(line);
+        output.write_closing_tag(tag);
+        output.push('\n');
+    }
+    output.write_closing_tag("code");
+    output.write_closing_tag("pre");
+    if options.title.is_some() {
+        output.write_closing_tag("figure");
+    }
+}
@@ -0,0 +1,45 @@
+use std::io::{BufRead, BufReader, Read};
+
+use crate::{escape, parser::Context};
+
+/// Parses a line comment, `%%% text`. Three markers are used, as text can start with a `%` and
+/// highlighted text starts with `%%`.
+pub fn parse_line_comment(line: &str) -> Option<&str> {
+    line.strip_prefix("%%%").map(str::trim)
+}
+
+/// Reads a `<comment>` block up to its closing `</>`, writing it as an HTML comment if comments
+/// are kept
+pub fn parse_comment_block<T>(reader: &mut BufReader<T>, output: &mut String, ctx: &Context)
+where
+    T: Read,
+{
+    let mut comment = String::new();
+    let mut lnbuf = String::new();
+    while let Ok(length) = reader.read_line(&mut lnbuf) {
+        if length == 0 || lnbuf.trim_end() == "</>" {
+            break;
+        }
+        comment.push_str(&lnbuf);
+        lnbuf.clear();
+    }
+    write_comment(output, comment.trim_end(), ctx);
+}
+
+/// Writes a comment as an HTML comment if the `keep_comments` option is set, and leaves it out
+/// otherwise
+pub fn write_comment(output: &mut String, comment: &str, ctx: &Context) {
+    if !ctx.options.keep_comments {
+        return;
+    }
+    // `--` could end the comment early
+    let mut comment = comment.to_string();
+    while comment.contains("--") {
+        comment = comment.replace("--", "- -");
+    }
+    output.push_str(escape!("<"));
+    output.push_str("!-- ");
+    output.push_str(&comment);
+    output.push_str(" --");
+    output.push_str(escape!(">"));
+}
@@ -0,0 +1,67 @@
+use std::io::{BufRead, BufReader};
+
+use crate::{
+    extensions::HtmlWriting,
+    parser::{Context, parse_text, peek_line},
+};
+
+enum DefinitionItem<'a> {
+    Term(&'a str),
+    /// The paragraphs of a definition
+    Definition(Vec<&'a str>),
+}
+
+/// Whether a line continues a definition list, checked before the line is read so the line after
+/// the list is left for the parser
+#[inline(always)]
+fn continues_definition_list(line: &str) -> bool {
+    line.starts_with("; ") || line.starts_with(": ") || line.starts_with(":: ")
+}
+
+/// Parses a definition list. Terms start with `; `, definitions with `: `, and `:: ` adds another
+/// paragraph to the definition above it.
+pub fn parse_definition_list(
+    reader: &mut BufReader<&[u8]>,
+    mut lnbuf: String,
+    ctx: &Context,
+) -> String {
+    while continues_definition_list(&peek_line(reader)) {
+        if reader.read_line(&mut lnbuf).is_err() {
+            break;
+        }
+    }
+
+    let mut items = vec![];
+    for line in lnbuf.lines() {
+        if let Some(term) = line.strip_prefix("; ") {
+            items.push(DefinitionItem::Term(term.trim()));
+        } else if let Some(paragraph) = line.strip_prefix(":: ") {
+            match items.last_mut() {
+                Some(DefinitionItem::Definition(paragraphs)) => paragraphs.push(paragraph.trim()),
+                _ => items.push(DefinitionItem::Definition(vec![paragraph.trim()])),
+            }
+        } else if let Some(definition) = line.strip_prefix(": ") {
+            items.push(DefinitionItem::Definition(vec![definition.trim()]));
+        }
+    }
+
+    let mut output = String::new();
+    output.write_opening_tag("dl", &[]);
+    for item in items {
+        match item {
+            DefinitionItem::Term(term) => output.write_tag("dt", &parse_text(term, ctx), &[]),
+            DefinitionItem::Definition(paragraphs) if paragraphs.len() == 1 => {
+                output.write_tag("dd", &parse_text(paragraphs[0], ctx), &[])
+            }
+            DefinitionItem::Definition(paragraphs) => {
+                output.write_opening_tag("dd", &[]);
+                for paragraph in paragraphs {
+                    output.write_tag("p", &parse_text(paragraph, ctx), &[]);
+                }
+                output.write_closing_tag("dd");
+            }
+        }
+    }
+    output.write_closing_tag("dl");
+    output
+}
@@ -0,0 +1,50 @@
+use std::io::{BufReader, Read};
+
+use crate::{
+    attributes::unqoute,
+    directives::{directive_options, read_block_body, warn_unknown_option},
+    extensions::HtmlWriting,
+    parser::{Context, parse_nested, parse_text},
+};
+
+/// Everything that can be passed to a `<details>` opener, e.g. `<details title="FAQ" open>`
+#[derive(Debug, Default)]
+pub struct DetailsOptions<'a> {
+    summary: Option<&'a str>,
+    open: bool,
+}
+
+/// Parses the options of a `<details>` opener
+pub fn parse_details_options(info: &str) -> DetailsOptions<'_> {
+    let mut options = DetailsOptions::default();
+    for token in directive_options("details", info) {
+        match token.split_once('=') {
+            Some(("title", summary)) => options.summary = Some(unqoute(summary)),
+            None if token == "open" => options.open = true,
+            _ => warn_unknown_option("details", token),
+        }
+    }
+    options
+}
+
+/// Renders a collapsible block, whose body is parsed as FXG
+pub fn parse_details<T>(
+    reader: &mut BufReader<T>,
+    output: &mut String,
+    options: &DetailsOptions,
+    ctx: &mut Context,
+) where
+    T: Read,
+{
+    let body = read_block_body(reader);
+    if options.open {
+        output.write_opening_tag("details", &[("open", "")]);
+    } else {
+        output.write_opening_tag("details", &[]);
+    }
+    if let Some(summary) = options.summary {
+        output.write_tag("summary", &parse_text(summary, ctx), &[]);
+    }
+    output.push_str(&parse_nested(&body, ctx));
+    output.write_closing_tag("details");
+}
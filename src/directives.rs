@@ -0,0 +1,144 @@
+use std::io::{BufRead, BufReader, Read};
+
+use phf_macros::phf_map;
+
+use crate::{attributes::split_tokens, codeblocks::CodeblockOptions, warn};
+
+/// Blocks that are opened like a codeblock, i.e. `<note>`, but are not code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Directive {
+    Toc,
+    Math,
+    Admonition,
+    Details,
+    Html,
+    Comment,
+    Verse,
+}
+
+impl Directive {
+    /// Whether the body of the block is parsed as FXG, so blocks can be nested inside of it
+    fn has_parsed_body(&self) -> bool {
+        matches!(self, Self::Admonition | Self::Details | Self::Verse)
+    }
+
+    /// Whether the body of the block is taken as-is, so it can't contain other blocks
+    fn has_raw_body(&self) -> bool {
+        matches!(self, Self::Math | Self::Html | Self::Comment)
+    }
+}
+
+/// Names of all directives. An opener with any other name is a codeblock in that language.
+static DIRECTIVES: phf::Map<&'static str, Directive> = phf_map! {
+    "toc" => Directive::Toc,
+    "math" => Directive::Math,
+    "note" => Directive::Admonition,
+    "tip" => Directive::Admonition,
+    "warning" => Directive::Admonition,
+    "danger" => Directive::Admonition,
+    "details" => Directive::Details,
+    "rawhtml" => Directive::Html,
+    "comment" => Directive::Comment,
+    "verse" => Directive::Verse,
+};
+
+#[inline(always)]
+pub fn is_directive(name: &str) -> bool {
+    DIRECTIVES.contains_key(name)
+}
+
+/// Splits the opener of a directive into the name of the directive and the rest of the opener
+pub fn parse_directive(line: &str) -> Option<(Directive, &str, &str)> {
+    let info = line.strip_prefix('<')?.strip_suffix('>')?;
+    let (name, rest) = info.split_once(char::is_whitespace).unwrap_or((info, ""));
+    DIRECTIVES
+        .get(name)
+        .map(|directive| (*directive, name, rest.trim()))
+}
+
+/// Splits the options of a directive into tokens. If a qoute is never closed, all options are
+/// ignored.
+pub fn directive_options<'a>(name: &str, info: &'a str) -> Vec<&'a str> {
+    split_tokens(info).unwrap_or_else(|| {
+        warn(&format!(
+            "Options of <{name}> contain an unclosed qoute, ignoring them..."
+        ));
+        vec![]
+    })
+}
+
+#[inline(always)]
+pub fn warn_unknown_option(name: &str, option: &str) {
+    warn(&format!(
+        "Unknown option {option} in <{name}>, ignoring it..."
+    ));
+}
+
+/// Warns about every option that is given to a directive that doesn't take any
+pub fn warn_options(name: &str, info: &str) {
+    for option in directive_options(name, info) {
+        warn_unknown_option(name, option);
+    }
+}
+
+/// Whether a line opens a block whose body is taken as-is, i.e. a codeblock or a comment
+fn opens_raw_body(line: &str) -> bool {
+    match parse_directive(line) {
+        Some((directive, _, _)) => directive.has_raw_body(),
+        None => line
+            .strip_prefix('<')
+            .and_then(|line| line.strip_suffix('>'))
+            .and_then(CodeblockOptions::parse)
+            .is_some_and(|options| !options.is_include()),
+    }
+}
+
+/// Returns the lines of a document that are not inside of a codeblock, math, raw HTML or a
+/// comment, so definitions can be collected before the document is rendered
+pub fn lines_outside_raw_bodies(source: &str) -> impl Iterator<Item = &str> {
+    let mut in_raw_body = false;
+    source.lines().filter(move |line| {
+        if in_raw_body {
+            in_raw_body = line.trim_end() != "</>";
+            return false;
+        }
+        in_raw_body = opens_raw_body(line.trim());
+        !in_raw_body
+    })
+}
+
+/// Reads the body of a block up to its closing `</>`. Blocks inside of it are closed by their own
+/// `</>`, and codeblocks, math, raw HTML and comments are skipped over, so their contents can't
+/// close the block.
+pub fn read_block_body<T>(reader: &mut BufReader<T>) -> String
+where
+    T: Read,
+{
+    let mut body = String::new();
+    let mut lnbuf = String::new();
+    let mut depth = 0;
+    let mut in_code = false;
+    while let Ok(length) = reader.read_line(&mut lnbuf) {
+        if length == 0 {
+            break;
+        }
+        let line = lnbuf.trim_end();
+        if in_code {
+            in_code = line != "</>";
+        } else if line.trim() == "</>" {
+            if depth == 0 {
+                break;
+            }
+            depth -= 1;
+        } else if parse_directive(line.trim())
+            .is_some_and(|(directive, _, _)| directive.has_parsed_body())
+        {
+            depth += 1;
+        } else {
+            in_code = opens_raw_body(line.trim());
+        }
+        body.push_str(&lnbuf);
+        lnbuf.clear();
+    }
+    body
+}
@@ -0,0 +1,134 @@
+use std::borrow::Cow;
+
+use phf_macros::phf_map;
+
+use crate::{autolinks::is_url, parser::Context};
+
+/// Emoji by shortcode, using the names GitHub and Slack use
+static EMOJI: phf::Map<&'static str, &'static str> = phf_map! {
+    "+1" => "👍",
+    "-1" => "👎",
+    "100" => "💯",
+    "angry" => "😠",
+    "arrow_down" => "⬇️",
+    "arrow_left" => "⬅️",
+    "arrow_right" => "➡️",
+    "arrow_up" => "⬆️",
+    "beer" => "🍺",
+    "bell" => "🔔",
+    "blush" => "😊",
+    "book" => "📖",
+    "boom" => "💥",
+    "bug" => "🐛",
+    "bulb" => "💡",
+    "cake" => "🍰",
+    "calendar" => "📆",
+    "cat" => "🐱",
+    "check" => "✔️",
+    "clap" => "👏",
+    "coffee" => "☕",
+    "confused" => "😕",
+    "construction" => "🚧",
+    "cry" => "😢",
+    "dog" => "🐶",
+    "email" => "📧",
+    "eyes" => "👀",
+    "fire" => "🔥",
+    "flushed" => "😳",
+    "gift" => "🎁",
+    "grin" => "😁",
+    "grinning" => "😀",
+    "heart" => "❤️",
+    "heavy_check_mark" => "✔️",
+    "hourglass" => "⌛",
+    "hugs" => "🤗",
+    "information_source" => "ℹ️",
+    "joy" => "😂",
+    "key" => "🔑",
+    "laughing" => "😆",
+    "link" => "🔗",
+    "lock" => "🔒",
+    "mag" => "🔍",
+    "memo" => "📝",
+    "moon" => "🌙",
+    "muscle" => "💪",
+    "no_entry" => "⛔",
+    "ok_hand" => "👌",
+    "package" => "📦",
+    "party_popper" => "🎉",
+    "pencil2" => "✏️",
+    "point_down" => "👇",
+    "point_left" => "👈",
+    "point_right" => "👉",
+    "point_up" => "☝️",
+    "pray" => "🙏",
+    "question" => "❓",
+    "raised_hands" => "🙌",
+    "recycle" => "♻️",
+    "rocket" => "🚀",
+    "rofl" => "🤣",
+    "rotating_light" => "🚨",
+    "scream" => "😱",
+    "see_no_evil" => "🙈",
+    "shrug" => "🤷",
+    "skull" => "💀",
+    "sleeping" => "😴",
+    "slightly_smiling_face" => "🙂",
+    "smile" => "😄",
+    "smiley" => "😃",
+    "smirk" => "😏",
+    "sob" => "😭",
+    "sparkles" => "✨",
+    "star" => "⭐",
+    "sunglasses" => "😎",
+    "sunny" => "☀️",
+    "sweat_smile" => "😅",
+    "tada" => "🎉",
+    "thinking" => "🤔",
+    "thumbsdown" => "👎",
+    "thumbsup" => "👍",
+    "trophy" => "🏆",
+    "wave" => "👋",
+    "warning" => "⚠️",
+    "white_check_mark" => "✅",
+    "wink" => "😉",
+    "wrench" => "🔧",
+    "x" => "❌",
+    "zap" => "⚡",
+    "zzz" => "💤",
+};
+
+/// Replaces known shortcodes like `:tada:` with their emoji, if the `emoji` option is set.
+/// Unknown shortcodes and URLs are left as they are.
+pub fn replace_shortcodes<'a>(text: &'a str, ctx: &Context) -> Cow<'a, str> {
+    if !ctx.options.emoji || !text.contains(':') {
+        return Cow::Borrowed(text);
+    }
+    let mut output = String::with_capacity(text.len());
+    for word in text.split_inclusive(char::is_whitespace) {
+        if is_url(word) {
+            output.push_str(word);
+            continue;
+        }
+        let mut rest = word;
+        while let Some(start) = rest.find(':') {
+            let after = &rest[start + 1..];
+            match after
+                .find(':')
+                .and_then(|end| EMOJI.get(&after[..end]).map(|emoji| (end, emoji)))
+            {
+                Some((end, emoji)) => {
+                    output.push_str(&rest[..start]);
+                    output.push_str(emoji);
+                    rest = &after[end + 1..];
+                }
+                None => {
+                    output.push_str(&rest[..=start]);
+                    rest = after;
+                }
+            }
+        }
+        output.push_str(rest);
+    }
+    Cow::Owned(output)
+}
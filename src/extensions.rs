@@ -6,6 +6,7 @@ pub trait HtmlWriting {
     fn write_tag(&mut self, tag: &str, contents: &str, tag_data: TagData);
     fn write_opening_tag(&mut self, tag: &str, tag_data: TagData);
     fn write_closing_tag(&mut self, tag: &str);
+    fn write_attribute(&mut self, key: &str, value: &str);
 }
 
 impl HtmlWriting for String {
@@ -13,21 +14,50 @@ impl HtmlWriting for String {
     fn write_opening_tag(&mut self, tag: &str, tag_data: TagData) {
         self.push_str(escape!("<"));
         self.push_str(tag);
+        // an element can get classes from several places, i.e. an attribute block and an option
+        let classes = tag_data
+            .iter()
+            .filter(|(key, _)| *key == "class")
+            .map(|(_, class)| *class)
+            .collect::<Vec<_>>()
+            .join(" ");
+        let mut wrote_class = false;
         for data in tag_data {
-            self.push(' ');
-            self.push_str(data.0);
-            self.push('=');
-            self.push_str(escape!("\""));
-            self.push_str(data.1);
-            self.push_str(escape!("\""));
+            if data.0 == "class" {
+                if wrote_class {
+                    continue;
+                }
+                wrote_class = true;
+                self.write_attribute(data.0, &classes);
+            } else {
+                self.write_attribute(data.0, data.1);
+            }
         }
         self.push_str(escape!(">"));
     }
 
+    #[inline(always)]
+    fn write_attribute(&mut self, key: &str, value: &str) {
+        self.push(' ');
+        self.push_str(key);
+        self.push('=');
+        self.push_str(escape!("\""));
+        // `<`, `>` and `&` are escaped along with the rest of the document
+        for c in value.chars() {
+            if c == '"' {
+                self.push_str(escape!("&"));
+                self.push_str("quot;");
+            } else {
+                self.push(c);
+            }
+        }
+        self.push_str(escape!("\""));
+    }
+
     #[inline(always)]
     fn write_closing_tag(&mut self, tag: &str) {
         self.push_str(escape!("<"));
-        self.push_str("/");
+        self.push('/');
         self.push_str(tag);
         self.push_str(escape!(">"));
     }
@@ -0,0 +1,152 @@
+use crate::{extensions::HtmlWriting, parser::Context, parser::parse_text, warn};
+
+/// Stands in for a footnote reference until the footnotes are numbered
+pub const FOOTNOTE_PLACEHOLDER: char = '\u{E012}';
+
+#[inline(always)]
+pub fn is_footnote_name(name: &str) -> bool {
+    !name.is_empty() && !name.contains(|c: char| c.is_whitespace() || c == '"' || c == '<')
+}
+
+/// Parses a footnote definition line, `<^name>: text`, storing the footnote in the context
+pub fn parse_footnote_definition(line: &str, ctx: &mut Context) -> bool {
+    let Some((name, text)) = line
+        .strip_prefix("<^")
+        .and_then(|line| line.split_once(">:"))
+        .filter(|(name, _)| is_footnote_name(name))
+    else {
+        return false;
+    };
+    if ctx.footnotes.contains_key(name) {
+        warn(&format!(
+            "Footnote {name} is defined more than once, using the last definition..."
+        ));
+    }
+    let text = parse_text(text.trim(), ctx);
+    ctx.footnotes.insert(name.to_string(), text);
+    true
+}
+
+/// Removes footnote references like `<^name>` from the source of a heading, so they don't end
+/// up in its ID
+pub fn remove_footnote_references(source: &str) -> String {
+    let mut output = String::new();
+    let mut rest = source;
+    while let Some(start) = rest.find("<^") {
+        output.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after.split_once('>') {
+            Some((name, remainder)) if is_footnote_name(name) => rest = remainder,
+            _ => {
+                output.push_str("<^");
+                rest = after;
+            }
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+/// Removes rendered footnote references from text that is repeated elsewhere, like the contents
+/// of a heading in a table of contents
+pub fn strip_footnote_references(text: &str) -> String {
+    text.split(FOOTNOTE_PLACEHOLDER).step_by(2).collect()
+}
+
+/// Numbers the footnotes in order of their first reference, and adds a list of all of them to
+/// the end of the document
+pub fn write_footnotes(output: String, ctx: &Context) -> String {
+    // names of the footnotes in order of their number, and how often they were referenced
+    let mut numbered: Vec<(&str, usize)> = vec![];
+    let mut result = resolve_references(&output, ctx, &mut numbered);
+
+    let mut section = String::new();
+    let mut idx = 0;
+    // footnotes can reference other footnotes, which are numbered while rendering this list
+    while idx < numbered.len() {
+        let name = numbered[idx].0;
+        let text = resolve_references(&ctx.footnotes[name], ctx, &mut numbered);
+        section.write_opening_tag("li", &[("id", &format!("fxg-fn-{name}"))]);
+        section.push_str(&text);
+        for reference in 1..=numbered[idx].1 {
+            section.push(' ');
+            section.write_tag(
+                "a",
+                "↩",
+                &[
+                    ("class", "fxg-backref"),
+                    ("href", &format!("#{}", reference_id(name, reference))),
+                ],
+            );
+        }
+        section.write_closing_tag("li");
+        idx += 1;
+    }
+
+    let mut unreferenced = ctx
+        .footnotes
+        .keys()
+        .filter(|name| !numbered.iter().any(|(numbered, _)| numbered == name))
+        .collect::<Vec<_>>();
+    unreferenced.sort();
+    for name in unreferenced {
+        warn(&format!("Footnote {name} is never referenced"));
+    }
+
+    if !section.is_empty() {
+        result.write_opening_tag("section", &[("class", "fxg-footnotes")]);
+        result.write_tag("ol", &section, &[]);
+        result.write_closing_tag("section");
+    }
+    result
+}
+
+fn resolve_references<'a>(
+    output: &str,
+    ctx: &'a Context,
+    numbered: &mut Vec<(&'a str, usize)>,
+) -> String {
+    let mut parts = output.split(FOOTNOTE_PLACEHOLDER);
+    let mut result = parts.next().unwrap_or_default().to_string();
+    while let (Some(name), rest) = (parts.next(), parts.next()) {
+        match ctx.footnotes.get_key_value(name) {
+            Some((name, _)) => {
+                let number = match numbered.iter().position(|(numbered, _)| numbered == name) {
+                    Some(idx) => idx,
+                    None => {
+                        numbered.push((name, 0));
+                        numbered.len() - 1
+                    }
+                };
+                numbered[number].1 += 1;
+                result.write_opening_tag("sup", &[("class", "fxg-fnref")]);
+                result.write_tag(
+                    "a",
+                    &(number + 1).to_string(),
+                    &[
+                        ("id", &reference_id(name, numbered[number].1)),
+                        ("href", &format!("#fxg-fn-{name}")),
+                    ],
+                );
+                result.write_closing_tag("sup");
+            }
+            None => {
+                warn(&format!("Footnote {name} is referenced, but never defined"));
+                result.push_str("<^");
+                result.push_str(name);
+                result.push('>');
+            }
+        }
+        result.push_str(rest.unwrap_or_default());
+    }
+    result
+}
+
+/// The first reference to a footnote gets a plain ID, the ones after that are numbered
+fn reference_id(name: &str, reference: usize) -> String {
+    if reference == 1 {
+        format!("fxg-fnref-{name}")
+    } else {
+        format!("fxg-fnref-{name}-{reference}")
+    }
+}
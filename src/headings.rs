@@ -0,0 +1,188 @@
+use crate::{
+    attributes::{is_generic_attribute, parse_attribute, split_tokens},
+    extensions::HtmlWriting,
+    footnotes::{remove_footnote_references, strip_footnote_references},
+    parser::{Context, parse_text},
+    warn,
+};
+
+/// Stands in for the text of a cross-reference until all headings are known
+pub const CROSS_REFERENCE_PLACEHOLDER: char = '\u{E011}';
+
+/// A heading as it is listed in a table of contents
+#[derive(Debug)]
+pub struct Heading {
+    pub level: usize,
+    pub id: Option<String>,
+    pub contents: String,
+    /// Section number like `2.3.1`, only when headings are numbered
+    pub number: Option<String>,
+}
+
+/// Everything that can be put between `{` and `}` at the end of a heading
+#[derive(Debug, Default)]
+struct HeadingAttributes<'a> {
+    id: Option<&'a str>,
+    unnumbered: bool,
+    /// Classes and `data-` attributes
+    attributes: Vec<(&'a str, &'a str)>,
+}
+
+pub fn parse_title(line: &str, ctx: &mut Context) -> String {
+    let mut output = String::new();
+    let (prefix, _) = line.split_once(' ').unwrap();
+    let header_size = prefix.len();
+    // tags above <h6> don't exist
+    if header_size > 6 {
+        output.push_str(&parse_text(line, ctx));
+        return output;
+    }
+
+    if line.ends_with(&str::repeat("=", header_size)) {
+        let header_contents = line[header_size..line.len() - 1 - header_size].trim();
+        let (header_contents, attributes) =
+            split_attributes(header_contents).unwrap_or((header_contents, Default::default()));
+        let id = match attributes.id {
+            Some(id) => Some(ctx.explicit_id(id)),
+            None => {
+                let slug = slugify(&remove_footnote_references(header_contents));
+                // an empty heading has nothing to link to
+                (!slug.is_empty()).then(|| ctx.unique_id(&slug))
+            }
+        };
+        let number = (ctx.options.number_headings && header_size > 1 && !attributes.unnumbered)
+            .then(|| next_section_number(header_size, ctx));
+
+        let tag = format!("h{header_size}");
+        if ctx.options.sections {
+            // the section takes the ID of the heading, so linking to it shows the whole section
+            close_sections(&mut output, header_size, ctx);
+            ctx.open_sections.push(header_size);
+            match &id {
+                Some(id) => output.write_opening_tag("section", &[("id", id)]),
+                None => output.write_opening_tag("section", &[]),
+            }
+            output.write_opening_tag(&tag, &attributes.attributes);
+        } else {
+            let mut tag_data = attributes.attributes.clone();
+            if let Some(id) = &id {
+                tag_data.insert(0, ("id", id));
+            }
+            output.write_opening_tag(&tag, &tag_data);
+        }
+        if let Some(number) = &number {
+            output.write_tag("span", number, &[("class", "fxg-secnum")]);
+            output.push(' ');
+        }
+        let contents = parse_text(header_contents, ctx);
+        output.push_str(&contents);
+        ctx.headings.push(Heading {
+            level: header_size,
+            id: id.clone(),
+            contents: strip_footnote_references(&contents),
+            number,
+        });
+        if let Some(id) = id.filter(|_| ctx.options.permalinks) {
+            output.write_tag(
+                "a",
+                "#",
+                &[("class", "fxg-permalink"), ("href", &format!("#{id}"))],
+            );
+        }
+        output.write_closing_tag(&tag);
+    } else {
+        // parse the text normally
+        output.push_str(&parse_text(line, ctx));
+    }
+    output
+}
+
+/// Splits trailing attributes like `{#id .class}` or `{-}` off of the heading contents
+fn split_attributes(contents: &str) -> Option<(&str, HeadingAttributes<'_>)> {
+    let (contents, attributes) = contents.strip_suffix('}')?.rsplit_once('{')?;
+    if attributes.trim().is_empty() {
+        return None;
+    }
+    let mut output = HeadingAttributes::default();
+    for token in split_tokens(attributes)? {
+        if token == "-" {
+            output.unnumbered = true;
+            continue;
+        }
+        match parse_attribute(token)? {
+            ("id", id) if !id.contains('{') => output.id = Some(id),
+            (key, value) if key != "id" && is_generic_attribute(key) => {
+                output.attributes.push((key, value))
+            }
+            _ => return None,
+        }
+    }
+    Some((contents.trim_end(), output))
+}
+
+/// Closes all sections of headings that are at the same level as or below `level`. A level of 1
+/// closes every section.
+pub fn close_sections(output: &mut String, level: usize, ctx: &mut Context) {
+    while ctx
+        .open_sections
+        .last()
+        .is_some_and(|section_level| *section_level >= level)
+    {
+        output.write_closing_tag("section");
+        ctx.open_sections.pop();
+    }
+}
+
+/// Counts the heading, and returns its number. h2 is the highest numbered level, as h1 is
+/// usually the title of the document.
+fn next_section_number(level: usize, ctx: &mut Context) -> String {
+    let counters = &mut ctx.section_numbers;
+    counters[level - 2] += 1;
+    counters[level - 1..].fill(0);
+    counters[..level - 1]
+        .iter()
+        .map(usize::to_string)
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+/// Fills in the text of all cross-references, which is the number of the heading they refer to
+/// or its contents if it isn't numbered
+pub fn write_cross_references(output: String, ctx: &Context) -> String {
+    let mut parts = output.split(CROSS_REFERENCE_PLACEHOLDER);
+    let mut result = parts.next().unwrap_or_default().to_string();
+    while let (Some(id), rest) = (parts.next(), parts.next()) {
+        match ctx.headings.iter().find(|h| h.id.as_deref() == Some(id)) {
+            Some(heading) => result.push_str(heading.number.as_ref().unwrap_or(&heading.contents)),
+            None => {
+                warn(&format!(
+                    "Cross-reference to #{id} does not refer to a heading"
+                ));
+                result.push('#');
+                result.push_str(id);
+            }
+        }
+        result.push_str(rest.unwrap_or_default());
+    }
+    result
+}
+
+/// Turns the contents of a heading into an ID. Letters are lowercased, whitespace and hyphens
+/// become a single hyphen, and everything else that is not alphanumeric (such as markup) is
+/// dropped. Non-ASCII letters are kept as-is.
+pub fn slugify(contents: &str) -> String {
+    let mut slug = String::new();
+    let mut pending_hyphen = false;
+    for c in contents.chars() {
+        if c.is_alphanumeric() {
+            if pending_hyphen && !slug.is_empty() {
+                slug.push('-');
+            }
+            pending_hyphen = false;
+            slug.extend(c.to_lowercase());
+        } else if c.is_whitespace() || c == '-' {
+            pending_hyphen = true;
+        }
+    }
+    slug
+}
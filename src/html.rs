@@ -0,0 +1,295 @@
+use std::io::{BufRead, BufReader, Read};
+
+use crate::{escape, extensions::HtmlWriting, parser::Context, warn};
+
+/// Tags that are kept when raw HTML is sanitized
+const ALLOWED_TAGS: &[&str] = &[
+    "a",
+    "abbr",
+    "b",
+    "blockquote",
+    "br",
+    "caption",
+    "cite",
+    "code",
+    "dd",
+    "del",
+    "details",
+    "div",
+    "dl",
+    "dt",
+    "em",
+    "figcaption",
+    "figure",
+    "h1",
+    "h2",
+    "h3",
+    "h4",
+    "h5",
+    "h6",
+    "hr",
+    "i",
+    "img",
+    "ins",
+    "kbd",
+    "li",
+    "mark",
+    "ol",
+    "p",
+    "pre",
+    "q",
+    "s",
+    "small",
+    "span",
+    "strong",
+    "sub",
+    "summary",
+    "sup",
+    "table",
+    "tbody",
+    "td",
+    "tfoot",
+    "th",
+    "thead",
+    "tr",
+    "u",
+    "ul",
+];
+
+/// Tags that are removed along with their contents when raw HTML is sanitized
+const REMOVED_TAGS: &[&str] = &[
+    "script", "style", "iframe", "object", "embed", "template", "noscript", "textarea", "title",
+];
+
+/// Tags that never have a closing tag
+const VOID_TAGS: &[&str] = &["br", "hr", "img"];
+
+/// Attributes that are kept on every allowed tag
+const ALLOWED_ATTRIBUTES: &[&str] = &["class", "title", "lang", "dir"];
+
+/// Attributes that are kept on specific tags
+const ALLOWED_TAG_ATTRIBUTES: &[(&str, &str)] = &[
+    ("a", "href"),
+    ("img", "src"),
+    ("img", "alt"),
+    ("img", "width"),
+    ("img", "height"),
+    ("ol", "start"),
+    ("td", "colspan"),
+    ("td", "rowspan"),
+    ("th", "colspan"),
+    ("th", "rowspan"),
+    ("details", "open"),
+];
+
+/// Attributes that contain a URL, which have to use a safe scheme
+const URL_ATTRIBUTES: &[&str] = &["href", "src"];
+
+/// URL schemes that can't run code. URLs without a scheme are always allowed.
+const SAFE_SCHEMES: &[&str] = &["http", "https", "mailto"];
+
+/// A tag in raw HTML, like `<a href="/">` or `</a>`
+#[derive(Debug)]
+struct Tag<'a> {
+    name: String,
+    closing: bool,
+    attributes: Vec<(String, &'a str)>,
+    /// Length of the tag in the source
+    length: usize,
+}
+
+/// Renders a `<rawhtml>` block, whose contents are written to the output as-is, or sanitized if
+/// the `sanitize_html` option is set. Raw HTML is left out entirely in safe mode.
+pub fn parse_html_block<T>(reader: &mut BufReader<T>, output: &mut String, ctx: &Context)
+where
+    T: Read,
+{
+    let mut html = String::new();
+    let mut lnbuf = String::new();
+    while let Ok(length) = reader.read_line(&mut lnbuf) {
+        if length == 0 || lnbuf.trim_end() == "</>" {
+            break;
+        }
+        html.push_str(&lnbuf);
+        lnbuf.clear();
+    }
+
+    if ctx.options.safe {
+        warn("Raw HTML is not allowed in safe mode, leaving it out...");
+    } else if ctx.options.sanitize_html {
+        output.push_str(&sanitize_html(&html));
+    } else {
+        output.push_str(&protect_html(&html));
+    }
+}
+
+/// Replaces the characters that would otherwise be escaped with their placeholders, so the HTML
+/// ends up in the document unchanged
+fn protect_html(html: &str) -> String {
+    let mut output = String::with_capacity(html.len());
+    for c in html.chars() {
+        match c {
+            '<' => output.push_str(escape!("<")),
+            '>' => output.push_str(escape!(">")),
+            '&' => output.push_str(escape!("&")),
+            '"' => output.push_str(escape!("\"")),
+            c => output.push(c),
+        }
+    }
+    output
+}
+
+/// Whether a URL can't run code when it is followed, i.e. `javascript:` URLs
+pub fn is_safe_url(url: &str) -> bool {
+    // browsers ignore whitespace and control characters in schemes
+    let url = url
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace() && !c.is_control())
+        .collect::<String>();
+    let scheme_end = url.find([':', '/', '?', '#']).unwrap_or(url.len());
+    if url[scheme_end..].starts_with(':') {
+        let scheme = url[..scheme_end].to_ascii_lowercase();
+        SAFE_SCHEMES.contains(&scheme.as_str())
+    } else {
+        // a character reference could hide a colon, i.e. `javascript&#58;`
+        !url[..scheme_end].contains('&')
+    }
+}
+
+/// Keeps only allowed tags and attributes. Tags are balanced, so the HTML can't close elements
+/// around it.
+fn sanitize_html(html: &str) -> String {
+    let mut output = String::new();
+    let mut open_tags: Vec<String> = vec![];
+    // contents of tags like <script> are removed until their closing tag
+    let mut removing: Option<String> = None;
+    let mut rest = html;
+    while let Some(start) = rest.find('<') {
+        if removing.is_none() {
+            output.push_str(&protect_text(&rest[..start]));
+        }
+        rest = &rest[start..];
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.split_once("-->").map_or("", |(_, rest)| rest);
+            continue;
+        }
+        let Some(tag) = parse_tag(rest) else {
+            if removing.is_none() {
+                output.push('<');
+            }
+            rest = &rest[1..];
+            continue;
+        };
+        rest = &rest[tag.length..];
+
+        if let Some(removed) = &removing {
+            if tag.closing && tag.name == *removed {
+                removing = None;
+            }
+        } else if REMOVED_TAGS.contains(&tag.name.as_str()) {
+            if !tag.closing {
+                removing = Some(tag.name);
+            }
+        } else if !ALLOWED_TAGS.contains(&tag.name.as_str()) {
+            // the tag is left out, but its contents are kept
+        } else if tag.closing {
+            if let Some(idx) = open_tags.iter().rposition(|open| *open == tag.name) {
+                for open in open_tags.drain(idx..).rev() {
+                    output.write_closing_tag(&open);
+                }
+            }
+        } else {
+            write_sanitized_tag(&mut output, &tag);
+            if !VOID_TAGS.contains(&tag.name.as_str()) {
+                open_tags.push(tag.name);
+            }
+        }
+    }
+    if removing.is_none() {
+        output.push_str(&protect_text(rest));
+    }
+    for open in open_tags.into_iter().rev() {
+        output.write_closing_tag(&open);
+    }
+    output
+}
+
+/// Character references in text are kept as they are, everything else is escaped
+#[inline(always)]
+fn protect_text(text: &str) -> String {
+    text.replace('&', escape!("&"))
+}
+
+fn write_sanitized_tag(output: &mut String, tag: &Tag) {
+    let values = tag
+        .attributes
+        .iter()
+        .filter(|(key, value)| {
+            let allowed = ALLOWED_ATTRIBUTES.contains(&key.as_str())
+                || ALLOWED_TAG_ATTRIBUTES.contains(&(tag.name.as_str(), key.as_str()));
+            allowed && (!URL_ATTRIBUTES.contains(&key.as_str()) || is_safe_url(value))
+        })
+        .map(|(key, value)| (key.as_str(), protect_text(value)))
+        .collect::<Vec<_>>();
+    let tag_data = values
+        .iter()
+        .map(|(key, value)| (*key, value.as_str()))
+        .collect::<Vec<_>>();
+    output.write_opening_tag(&tag.name, &tag_data);
+}
+
+/// Parses the tag at the start of `html`. Returns `None` if it isn't a tag, like the `<` in
+/// `a < b`.
+fn parse_tag(html: &str) -> Option<Tag<'_>> {
+    let mut rest = html.strip_prefix('<')?;
+    let closing = rest.starts_with('/');
+    if closing {
+        rest = &rest[1..];
+    }
+    let name_end = rest
+        .find(|c: char| !c.is_ascii_alphanumeric())
+        .unwrap_or(rest.len());
+    if !rest.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    let name = rest[..name_end].to_ascii_lowercase();
+    rest = &rest[name_end..];
+
+    let mut attributes = vec![];
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_ascii_whitespace() || c == '/');
+        if let Some(after) = rest.strip_prefix('>') {
+            return Some(Tag {
+                name,
+                closing,
+                attributes,
+                length: html.len() - after.len(),
+            });
+        }
+        let key_end = rest
+            .find(|c: char| c.is_ascii_whitespace() || ['=', '>', '/'].contains(&c))
+            .unwrap_or(rest.len());
+        if key_end == 0 {
+            // the tag is never closed
+            return None;
+        }
+        let key = rest[..key_end].to_ascii_lowercase();
+        rest = rest[key_end..].trim_start();
+        let Some(value) = rest.strip_prefix('=') else {
+            attributes.push((key, ""));
+            continue;
+        };
+        let value = value.trim_start();
+        let (value, after) = match value.chars().next() {
+            Some(qoute @ ('"' | '\'')) => value[1..].split_once(qoute)?,
+            _ => {
+                let end = value
+                    .find(|c: char| c.is_ascii_whitespace() || c == '>')
+                    .unwrap_or(value.len());
+                value.split_at(end)
+            }
+        };
+        attributes.push((key, value));
+        rest = after;
+    }
+}
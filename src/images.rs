@@ -0,0 +1,202 @@
+use std::{fs::File, io::Read};
+
+use crate::{
+    attributes::{is_generic_attribute, replace_id, reserve_id, split_attribute_block},
+    extensions::HtmlWriting,
+    html::is_safe_url,
+    parser::{Context, parse_text},
+    references::resolve_link_reference,
+    warn,
+};
+
+/// How much of an image is read to find its size. The size is at the start of PNG, GIF and WebP
+/// images, but can come after metadata in JPEG images.
+const IMAGE_HEADER_LENGTH: u64 = 256 * 1024;
+
+/// Attributes other than generic attributes that can be given to an image using an attribute block
+const IMAGE_ATTRIBUTES: &[&str] = &["width", "height", "loading", "title", "caption"];
+
+/// Splits the attribute block that can follow an image off of the text after it, like
+/// [`crate::links::split_link_attributes`] does for links
+pub fn split_image_attributes(text: &str) -> (Vec<(&str, &str)>, &str) {
+    match split_attribute_block(text) {
+        Some((attributes, remainder))
+            if attributes
+                .iter()
+                .all(|(key, _)| IMAGE_ATTRIBUTES.contains(key) || is_generic_attribute(key)) =>
+        {
+            (attributes, remainder)
+        }
+        _ => (vec![], text),
+    }
+}
+
+/// Writes an `<img>` element. Images are lazily loaded unless the attribute block says
+/// otherwise, and the size of local images is read from the image itself if it isn't given.
+pub fn write_image(
+    output: &mut String,
+    src: &str,
+    alt: Option<&str>,
+    attributes: &[(&str, &str)],
+    ctx: &Context,
+) {
+    if ctx.options.safe && !is_safe_url(src) {
+        warn(&format!(
+            "Image {src} is not allowed in safe mode, leaving out the image..."
+        ));
+        output.push_str(alt.unwrap_or_default());
+        return;
+    }
+
+    let mut tag_data = vec![("src", src)];
+    if let Some(alt) = alt {
+        tag_data.push(("alt", alt));
+    }
+
+    let attribute = |key| {
+        attributes
+            .iter()
+            .find(|(attribute, _)| *attribute == key)
+            .map(|(_, value)| *value)
+    };
+    let (width, height) = match (attribute("width"), attribute("height")) {
+        (None, None) => match local_image_size(src, ctx) {
+            Some((width, height)) => (Some(width.to_string()), Some(height.to_string())),
+            None => (None, None),
+        },
+        (width, height) => (width.map(str::to_string), height.map(str::to_string)),
+    };
+    if let Some(width) = &width {
+        tag_data.push(("width", width));
+    }
+    if let Some(height) = &height {
+        tag_data.push(("height", height));
+    }
+    if let Some(title) = attribute("title") {
+        tag_data.push(("title", title));
+    }
+    tag_data.push(("loading", attribute("loading").unwrap_or("lazy")));
+    let id = reserve_id(attributes, ctx);
+    tag_data.extend(
+        replace_id(attributes, id.as_deref())
+            .into_iter()
+            .filter(|(key, _)| is_generic_attribute(key)),
+    );
+
+    output.write_opening_tag("img", &tag_data);
+}
+
+/// Parses a line that consists of only an image with a caption, which becomes a `<figure>`
+pub fn parse_figure(line: &str, ctx: &Context) -> Option<String> {
+    let (contents, rest) = line.strip_prefix("<!")?.split_once('>')?;
+    let (attributes, rest) = split_image_attributes(rest);
+    let caption = attributes
+        .iter()
+        .find(|(key, _)| *key == "caption")
+        .map(|(_, caption)| *caption)?;
+    if !rest.trim().is_empty() {
+        return None;
+    }
+    let contents = resolve_link_reference(contents, ctx)?;
+    let (src, alt) = match contents.split_once(' ') {
+        Some((src, alt)) => (src, Some(alt)),
+        None => (contents.as_ref(), None),
+    };
+
+    let mut output = String::new();
+    output.write_opening_tag("figure", &[("class", "fxg-figure")]);
+    write_image(&mut output, src, alt, &attributes, ctx);
+    output.write_tag("figcaption", &parse_text(caption, ctx), &[]);
+    output.write_closing_tag("figure");
+    Some(output)
+}
+
+/// Reads the size of an image that is stored next to the document. Images that are not local, or
+/// can't be read, don't get a size. Untrusted documents can't make the renderer read files, so
+/// images never get a size in safe mode.
+fn local_image_size(src: &str, ctx: &Context) -> Option<(u32, u32)> {
+    if ctx.options.safe || src.contains(':') || src.starts_with('/') || src.is_empty() {
+        return None;
+    }
+    let path = ctx.options.base_dir.join(src.split(['?', '#']).next()?);
+    let file = File::open(&path).ok()?;
+    if !file.metadata().ok()?.is_file() {
+        return None;
+    }
+    let mut data = vec![];
+    file.take(IMAGE_HEADER_LENGTH).read_to_end(&mut data).ok()?;
+    let size = image_size(&data);
+    if size.is_none() {
+        warn(&format!(
+            "Size of image {} wasn't able to be read",
+            path.display()
+        ));
+    }
+    size
+}
+
+#[inline(always)]
+fn u16_be(data: &[u8], idx: usize) -> Option<u32> {
+    Some(u16::from_be_bytes(data.get(idx..idx + 2)?.try_into().ok()?) as u32)
+}
+
+#[inline(always)]
+fn u16_le(data: &[u8], idx: usize) -> Option<u32> {
+    Some(u16::from_le_bytes(data.get(idx..idx + 2)?.try_into().ok()?) as u32)
+}
+
+#[inline(always)]
+fn u24_le(data: &[u8], idx: usize) -> Option<u32> {
+    let bytes = data.get(idx..idx + 3)?;
+    Some(bytes[0] as u32 | (bytes[1] as u32) << 8 | (bytes[2] as u32) << 16)
+}
+
+/// Reads the width and height of a PNG, JPEG, GIF or WebP image from its header
+pub fn image_size(data: &[u8]) -> Option<(u32, u32)> {
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        let width = u32::from_be_bytes(data.get(16..20)?.try_into().ok()?);
+        let height = u32::from_be_bytes(data.get(20..24)?.try_into().ok()?);
+        Some((width, height))
+    } else if data.starts_with(b"GIF87a") || data.starts_with(b"GIF89a") {
+        Some((u16_le(data, 6)?, u16_le(data, 8)?))
+    } else if data.starts_with(b"RIFF") && data.get(8..12)? == b"WEBP" {
+        webp_size(data)
+    } else if data.starts_with(&[0xff, 0xd8]) {
+        jpeg_size(data)
+    } else {
+        None
+    }
+}
+
+fn webp_size(data: &[u8]) -> Option<(u32, u32)> {
+    match data.get(12..16)? {
+        b"VP8 " => Some((u16_le(data, 26)? & 0x3fff, u16_le(data, 28)? & 0x3fff)),
+        b"VP8L" => {
+            let bits = u32::from_le_bytes(data.get(21..25)?.try_into().ok()?);
+            Some(((bits & 0x3fff) + 1, ((bits >> 14) & 0x3fff) + 1))
+        }
+        b"VP8X" => Some((u24_le(data, 24)? + 1, u24_le(data, 27)? + 1)),
+        _ => None,
+    }
+}
+
+fn jpeg_size(data: &[u8]) -> Option<(u32, u32)> {
+    let mut idx = 2;
+    loop {
+        // segments start with one or more 0xff bytes, followed by the marker
+        while *data.get(idx)? == 0xff {
+            idx += 1;
+        }
+        let marker = *data.get(idx)?;
+        idx += 1;
+        match marker {
+            // start of frame, except for DHT, JPG and DAC, which share the range
+            0xc0..=0xcf if ![0xc4, 0xc8, 0xcc].contains(&marker) => {
+                return Some((u16_be(data, idx + 5)?, u16_be(data, idx + 3)?));
+            }
+            // standalone markers without a length
+            0x01 | 0xd0..=0xd8 => {}
+            _ => idx += u16_be(data, idx)? as usize,
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use crate::{
+    attributes::{is_generic_attribute, replace_id, reserve_id, split_attribute_block},
+    extensions::HtmlWriting,
+    html::is_safe_url,
+    parser::Context,
+    warn,
+};
+
+/// Attributes other than generic attributes that can be given to a link using an attribute block
+const LINK_ATTRIBUTES: &[&str] = &["title", "target", "rel"];
+
+/// Splits the attribute block that can follow a link off of the text after it. If there is no
+/// block, or the block contains attributes links can't have, no attributes are returned and the
+/// text is left as-is.
+pub fn split_link_attributes(text: &str) -> (Vec<(&str, &str)>, &str) {
+    match split_attribute_block(text) {
+        Some((attributes, remainder))
+            if attributes
+                .iter()
+                .all(|(key, _)| LINK_ATTRIBUTES.contains(key) || is_generic_attribute(key)) =>
+        {
+            (attributes, remainder)
+        }
+        _ => (vec![], text),
+    }
+}
+
+/// Links are off-site if they have a host, and that host is not the site itself or one of its
+/// subdomains
+fn is_external(href: &str, ctx: &Context) -> bool {
+    let rest = match href.split_once("://") {
+        Some((scheme, rest)) if !scheme.is_empty() && scheme.chars().all(char::is_alphanumeric) => {
+            rest
+        }
+        _ => match href.strip_prefix("//") {
+            Some(rest) => rest,
+            None => return false,
+        },
+    };
+    let host = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    let host = host.rsplit_once('@').map_or(host, |(_, host)| host);
+    let host = host.split(':').next().unwrap_or_default();
+    match &ctx.options.site_host {
+        Some(site) => host != site && !host.ends_with(&format!(".{site}")),
+        None => true,
+    }
+}
+
+/// Writes an `<a>` element with the attributes from its attribute block, and the attributes that
+/// are added to every off-site link
+pub fn write_link(
+    output: &mut String,
+    href: &str,
+    contents: &str,
+    attributes: &[(&str, &str)],
+    ctx: &Context,
+) {
+    if ctx.options.safe && !is_safe_url(href) {
+        warn(&format!(
+            "Link to {href} is not allowed in safe mode, leaving out the link..."
+        ));
+        output.push_str(contents);
+        return;
+    }
+
+    let id = reserve_id(attributes, ctx);
+    let mut rel = vec![];
+    let mut tag_data = vec![("href", href)];
+    for (key, value) in replace_id(attributes, id.as_deref()) {
+        if key == "rel" {
+            rel.extend(value.split_whitespace());
+        } else {
+            tag_data.push((key, value));
+        }
+    }
+
+    // pages opened in a new tab should not be able to access the page that opened them
+    if attributes.contains(&("target", "_blank")) {
+        rel.extend(["noopener", "noreferrer"]);
+    }
+    let external = is_external(href, ctx);
+    if external && (ctx.options.nofollow || ctx.options.safe) {
+        rel.push("nofollow");
+    }
+    // links in untrusted input are user-generated content
+    if external && ctx.options.safe {
+        rel.push("ugc");
+    }
+    let mut unique_rel = vec![];
+    for value in rel {
+        if !unique_rel.contains(&value) {
+            unique_rel.push(value);
+        }
+    }
+
+    let rel = unique_rel.join(" ");
+    if !rel.is_empty() {
+        tag_data.push(("rel", &rel));
+    }
+    if let Some(class) = ctx.options.external_class.as_deref().filter(|_| external) {
+        tag_data.push(("class", class));
+    }
+
+    output.write_tag("a", contents, &tag_data);
+}
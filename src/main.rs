@@ -1,14 +1,45 @@
 use std::{
-    collections::HashMap, env::args, fmt::Display, fs::File, io::{self, BufReader, Read}, path::PathBuf,
+    collections::HashMap,
+    env::args,
+    fmt::Display,
+    fs::File,
+    io::{BufReader, Read},
+    path::Path,
     process::exit,
 };
 
 use owo_colors::OwoColorize;
 use phf_macros::phf_map;
 
+use crate::{
+    parser::Options,
+    toc::{TocLevels, parse_level},
+};
+
+mod abbreviations;
+mod admonitions;
+mod attributes;
+mod autolinks;
 mod blockqoutes;
+mod codeblocks;
+mod comments;
+mod definitions;
+mod details;
+mod directives;
+mod emoji;
 mod extensions;
+mod footnotes;
+mod headings;
+mod html;
+mod images;
+mod links;
+mod math;
+mod media;
 mod parser;
+mod references;
+mod toc;
+mod typography;
+mod verse;
 
 #[cfg(test)]
 #[path = "../test/mod.rs"]
@@ -39,7 +70,14 @@ static UNICODE_PLACEHOLDERS: phf::Map<&'static str, &'static str> = phf_map! {
     "!!" => "\u{E005}",
     "</>" => "\u{E006}",
     "\"" => "\u{E007}",
-    "\\" => "\u{E008}"
+    "\\" => "\u{E008}",
+    "??" => "\u{E009}",
+    "~~" => "\u{E00A}",
+    "%%" => "\u{E00B}",
+    "^^" => "\u{E00C}",
+    ",," => "\u{E00D}",
+    "@@" => "\u{E00E}",
+    "&" => "\u{E00F}"
 };
 
 #[inline]
@@ -68,9 +106,9 @@ fn parse_args() -> Args {
     };
     args.file = input_file;
     for arg in cli_args {
-        if arg.starts_with("--") {
-            if let Some((key, value)) = arg.split_once("=") {
-                args.options.insert(key[2..].to_string(), value.to_string());
+        if let Some(option) = arg.strip_prefix("--") {
+            if let Some((key, value)) = option.split_once("=") {
+                args.options.insert(key.to_string(), value.to_string());
             } else {
                 warn(&format!(
                     "Argument {} was not able to be parsed, ignoring...",
@@ -78,8 +116,8 @@ fn parse_args() -> Args {
                 ));
                 continue;
             }
-        } else if arg.starts_with("-") {
-            args.flags.push(arg[1..].to_string());
+        } else if let Some(flag) = arg.strip_prefix("-") {
+            args.flags.push(flag.to_string());
         } else {
             warn(&format!(
                 "Argument {} was not able to be parsed, ignoring...",
@@ -89,21 +127,68 @@ fn parse_args() -> Args {
             continue;
         }
     }
-    return args;
+    args
+}
+
+fn render_options(args: &Args) -> Options {
+    let mut options = Options::default();
+    if args.file != "-" {
+        // includes etc. are relative to the document, not to the working directory
+        if let Some(parent) = Path::new(&args.file).parent() {
+            options.base_dir = parent.to_path_buf();
+        }
+    }
+    options.permalinks = args.flags.iter().any(|flag| flag == "permalinks");
+    options.number_headings = args.flags.iter().any(|flag| flag == "number-headings");
+    options.sections = args.flags.iter().any(|flag| flag == "sections");
+    options.nofollow = args.flags.iter().any(|flag| flag == "nofollow");
+    options.autolink = args.flags.iter().any(|flag| flag == "autolink");
+    options.sanitize_html = args.flags.iter().any(|flag| flag == "sanitize-html");
+    options.safe = args.flags.iter().any(|flag| flag == "safe");
+    options.smart_typography = args.flags.iter().any(|flag| flag == "smart");
+    options.lang = args.options.get("lang").cloned();
+    options.emoji = args.flags.iter().any(|flag| flag == "emoji");
+    options.keep_comments = args.flags.iter().any(|flag| flag == "keep-comments");
+    options.site_host = args.options.get("site").cloned();
+    options.external_class = args.options.get("external-class").cloned();
+    if args.flags.iter().any(|flag| flag == "toc") {
+        let mut levels = TocLevels::default();
+        if let Some(min) = args.options.get("toc-min") {
+            levels.min = parse_level_option(min);
+        }
+        if let Some(max) = args.options.get("toc-max") {
+            levels.max = parse_level_option(max);
+        }
+        if levels.min > levels.max {
+            error(
+                &format!("--toc-min={} is above --toc-max={}", levels.min, levels.max),
+                1,
+            );
+        }
+        options.toc = Some(levels);
+    }
+    options
+}
+
+fn parse_level_option(level: &str) -> usize {
+    parse_level(level).unwrap_or_else(|| error(&format!("{level} is not a heading level (1-6)"), 1))
 }
 
 fn main() {
     let args = parse_args();
+    let options = render_options(&args);
     if args.file == "-" {
         // read from stdio
         let mut stdin = std::io::stdin();
         let mut data: Vec<u8> = vec![];
-        stdin.read_to_end(&mut data);
+        if let Err(e) = stdin.read_to_end(&mut data) {
+            error(&format!("Input wasn't able to be read ({e})"), 2);
+        }
         let mut buf_reader = BufReader::new(data.as_slice());
-        let output = crate::parser::parse(&mut buf_reader);
+        let output = crate::parser::parse_with_options(&mut buf_reader, &options);
         print!("{output}");
     } else {
-        let source_file = match File::open(args.file) {
+        let source_file = match File::open(&args.file) {
             Ok(v) => v,
             Err(e) => {
                 eprintln!(
@@ -115,7 +200,7 @@ fn main() {
             }
         };
         let mut reader = BufReader::new(source_file);
-        let output = crate::parser::parse(&mut reader);
+        let output = crate::parser::parse_with_options(&mut reader, &options);
         print!("{output}");
     }
 }
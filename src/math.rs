@@ -0,0 +1,357 @@
+use std::io::{BufRead, BufReader, Read};
+
+use phf_macros::phf_map;
+
+use crate::{extensions::HtmlWriting, warn};
+
+/// Commands that stand for a single symbol, with the MathML element it is rendered as
+static SYMBOLS: phf::Map<&'static str, (&'static str, &'static str)> = phf_map! {
+    "alpha" => ("mi", "α"),
+    "beta" => ("mi", "β"),
+    "gamma" => ("mi", "γ"),
+    "delta" => ("mi", "δ"),
+    "epsilon" => ("mi", "ε"),
+    "zeta" => ("mi", "ζ"),
+    "eta" => ("mi", "η"),
+    "theta" => ("mi", "θ"),
+    "iota" => ("mi", "ι"),
+    "kappa" => ("mi", "κ"),
+    "lambda" => ("mi", "λ"),
+    "mu" => ("mi", "μ"),
+    "nu" => ("mi", "ν"),
+    "xi" => ("mi", "ξ"),
+    "pi" => ("mi", "π"),
+    "rho" => ("mi", "ρ"),
+    "sigma" => ("mi", "σ"),
+    "tau" => ("mi", "τ"),
+    "upsilon" => ("mi", "υ"),
+    "phi" => ("mi", "φ"),
+    "chi" => ("mi", "χ"),
+    "psi" => ("mi", "ψ"),
+    "omega" => ("mi", "ω"),
+    "Gamma" => ("mi", "Γ"),
+    "Delta" => ("mi", "Δ"),
+    "Theta" => ("mi", "Θ"),
+    "Lambda" => ("mi", "Λ"),
+    "Xi" => ("mi", "Ξ"),
+    "Pi" => ("mi", "Π"),
+    "Sigma" => ("mi", "Σ"),
+    "Phi" => ("mi", "Φ"),
+    "Psi" => ("mi", "Ψ"),
+    "Omega" => ("mi", "Ω"),
+    "infty" => ("mi", "∞"),
+    "partial" => ("mi", "∂"),
+    "nabla" => ("mi", "∇"),
+    "ell" => ("mi", "ℓ"),
+    "sum" => ("mo", "∑"),
+    "prod" => ("mo", "∏"),
+    "int" => ("mo", "∫"),
+    "oint" => ("mo", "∮"),
+    "pm" => ("mo", "±"),
+    "mp" => ("mo", "∓"),
+    "times" => ("mo", "×"),
+    "div" => ("mo", "÷"),
+    "cdot" => ("mo", "⋅"),
+    "ldots" => ("mo", "…"),
+    "cdots" => ("mo", "⋯"),
+    "leq" => ("mo", "≤"),
+    "geq" => ("mo", "≥"),
+    "neq" => ("mo", "≠"),
+    "approx" => ("mo", "≈"),
+    "equiv" => ("mo", "≡"),
+    "sim" => ("mo", "∼"),
+    "propto" => ("mo", "∝"),
+    "to" => ("mo", "→"),
+    "rightarrow" => ("mo", "→"),
+    "leftarrow" => ("mo", "←"),
+    "Rightarrow" => ("mo", "⇒"),
+    "Leftrightarrow" => ("mo", "⇔"),
+    "in" => ("mo", "∈"),
+    "notin" => ("mo", "∉"),
+    "subset" => ("mo", "⊂"),
+    "subseteq" => ("mo", "⊆"),
+    "cup" => ("mo", "∪"),
+    "cap" => ("mo", "∩"),
+    "forall" => ("mo", "∀"),
+    "exists" => ("mo", "∃"),
+    "neg" => ("mo", "¬"),
+    "land" => ("mo", "∧"),
+    "lor" => ("mo", "∨"),
+    "sin" => ("mi", "sin"),
+    "cos" => ("mi", "cos"),
+    "tan" => ("mi", "tan"),
+    "log" => ("mi", "log"),
+    "ln" => ("mi", "ln"),
+    "exp" => ("mi", "exp"),
+    "lim" => ("mo", "lim"),
+    "max" => ("mo", "max"),
+    "min" => ("mo", "min"),
+    "{" => ("mo", "{"),
+    "}" => ("mo", "}"),
+    "," => ("mspace", ""),
+};
+
+/// An error in a math expression, with the byte range of the source it applies to
+#[derive(Debug)]
+pub struct MathError {
+    pub start: usize,
+    pub end: usize,
+    pub message: String,
+}
+
+struct MathParser<'a> {
+    source: &'a str,
+    pos: usize,
+}
+
+impl<'a> MathParser<'a> {
+    fn error<T>(&self, start: usize, message: &str) -> Result<T, MathError> {
+        Err(MathError {
+            start,
+            end: self.pos.max(start + 1),
+            message: message.to_string(),
+        })
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.source[self.pos..].chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek().filter(|c| c.is_whitespace()) {
+            self.pos += c.len_utf8();
+        }
+    }
+
+    /// Parses atoms with their sub- and superscripts until the end of the source, or until the
+    /// closing brace of the group that starts at `group_start`
+    fn parse_row(&mut self, group_start: Option<usize>) -> Result<String, MathError> {
+        let mut output = String::new();
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                None => match group_start {
+                    Some(start) => return self.error(start, "group is never closed"),
+                    None => return Ok(output),
+                },
+                Some('}') if group_start.is_some() => return Ok(output),
+                Some('}') => {
+                    self.pos += 1;
+                    return self.error(self.pos - 1, "unexpected }");
+                }
+                Some(_) => output.push_str(&self.parse_scripts()?),
+            }
+        }
+    }
+
+    fn parse_scripts(&mut self) -> Result<String, MathError> {
+        let base = match self.peek() {
+            // scripts without anything to attach to
+            Some('^' | '_') => {
+                let mut output = String::new();
+                output.write_tag("mrow", "", &[]);
+                output
+            }
+            _ => self.parse_atom()?,
+        };
+        let mut sub = None;
+        let mut sup = None;
+        loop {
+            self.skip_whitespace();
+            let start = self.pos;
+            let script = match self.peek() {
+                Some('_') => &mut sub,
+                Some('^') => &mut sup,
+                _ => break,
+            };
+            if script.is_some() {
+                self.pos += 1;
+                return self.error(start, "double script");
+            }
+            self.pos += 1;
+            *script = Some(self.parse_argument()?);
+        }
+        let mut output = String::new();
+        match (sub, sup) {
+            (None, None) => return Ok(base),
+            (Some(sub), None) => output.write_tag("msub", &(base + &sub), &[]),
+            (None, Some(sup)) => output.write_tag("msup", &(base + &sup), &[]),
+            (Some(sub), Some(sup)) => output.write_tag("msubsup", &(base + &sub + &sup), &[]),
+        }
+        Ok(output)
+    }
+
+    /// Parses the argument of a command or script, which is either a group or a single atom
+    fn parse_argument(&mut self) -> Result<String, MathError> {
+        self.skip_whitespace();
+        let start = self.pos;
+        match self.peek() {
+            None | Some('}' | '^' | '_') => self.error(start, "missing argument"),
+            Some(c) if c.is_ascii_digit() => {
+                // only a single digit, so x^23 is x² followed by 3 like in TeX
+                self.pos += 1;
+                let mut output = String::new();
+                output.write_tag("mn", &c.to_string(), &[]);
+                Ok(output)
+            }
+            Some(_) => self.parse_atom(),
+        }
+    }
+
+    fn parse_group(&mut self) -> Result<String, MathError> {
+        let start = self.pos;
+        // skip the {
+        self.pos += 1;
+        let contents = self.parse_row(Some(start))?;
+        self.pos += 1;
+        let mut output = String::new();
+        output.write_tag("mrow", &contents, &[]);
+        Ok(output)
+    }
+
+    fn parse_atom(&mut self) -> Result<String, MathError> {
+        let start = self.pos;
+        let Some(c) = self.peek() else {
+            return self.error(start, "unexpected end of math");
+        };
+        let mut output = String::new();
+        if c == '{' {
+            return self.parse_group();
+        } else if c == '\\' {
+            return self.parse_command();
+        } else if c.is_ascii_digit() {
+            let len = self.source[start..]
+                .find(|c: char| !c.is_ascii_digit() && c != '.')
+                .unwrap_or(self.source.len() - start);
+            self.pos += len;
+            output.write_tag("mn", &self.source[start..self.pos], &[]);
+        } else if c.is_alphabetic() {
+            self.pos += c.len_utf8();
+            output.write_tag("mi", &c.to_string(), &[]);
+        } else {
+            self.pos += c.len_utf8();
+            output.write_tag("mo", &c.to_string(), &[]);
+        }
+        Ok(output)
+    }
+
+    fn parse_command(&mut self) -> Result<String, MathError> {
+        let start = self.pos;
+        // skip the backslash
+        self.pos += 1;
+        let name_len = match self.source[self.pos..].find(|c: char| !c.is_ascii_alphabetic()) {
+            // commands that are a single symbol, like \{
+            Some(0) => self.peek().map_or(0, char::len_utf8),
+            Some(len) => len,
+            None => self.source.len() - self.pos,
+        };
+        let name = &self.source[self.pos..self.pos + name_len];
+        self.pos += name_len;
+
+        let mut output = String::new();
+        match name {
+            "" => return self.error(start, "missing command name"),
+            "frac" => {
+                let numerator = self.parse_argument()?;
+                let denominator = self.parse_argument()?;
+                output.write_tag("mfrac", &(numerator + &denominator), &[]);
+            }
+            "sqrt" => {
+                self.skip_whitespace();
+                if self.peek() == Some('[') {
+                    let index_start = self.pos;
+                    let Some(len) = self.source[self.pos..].find(']') else {
+                        self.pos = self.source.len();
+                        return self.error(index_start, "root index is never closed");
+                    };
+                    let index = &self.source[self.pos + 1..self.pos + len];
+                    self.pos += len + 1;
+                    let index = MathParser {
+                        source: index,
+                        pos: 0,
+                    }
+                    .parse_row(None)
+                    .map_err(|e| MathError {
+                        start: e.start + index_start + 1,
+                        end: e.end + index_start + 1,
+                        message: e.message,
+                    })?;
+                    let radicand = self.parse_argument()?;
+                    let mut index_row = String::new();
+                    index_row.write_tag("mrow", &index, &[]);
+                    output.write_tag("mroot", &(radicand + &index_row), &[]);
+                } else {
+                    output.write_tag("msqrt", &self.parse_argument()?, &[]);
+                }
+            }
+            "text" => {
+                self.skip_whitespace();
+                if self.peek() != Some('{') {
+                    return self.error(start, "\\text needs a {group}");
+                }
+                let Some(len) = self.source[self.pos..].find('}') else {
+                    let group_start = self.pos;
+                    self.pos = self.source.len();
+                    return self.error(group_start, "group is never closed");
+                };
+                output.write_tag("mtext", &self.source[self.pos + 1..self.pos + len], &[]);
+                self.pos += len + 1;
+            }
+            name => match SYMBOLS.get(name) {
+                Some(("mspace", _)) => {
+                    output.write_opening_tag("mspace", &[("width", "0.17em")]);
+                    output.write_closing_tag("mspace");
+                }
+                Some((tag, symbol)) => output.write_tag(tag, symbol, &[]),
+                None => return self.error(start, &format!("unknown command \\{name}")),
+            },
+        }
+        Ok(output)
+    }
+}
+
+/// Renders TeX-like math to MathML
+pub fn render_math(source: &str, display: bool) -> Result<String, MathError> {
+    let contents = MathParser { source, pos: 0 }.parse_row(None)?;
+    let mut output = String::new();
+    output.write_tag(
+        "math",
+        &contents,
+        &[("display", if display { "block" } else { "inline" })],
+    );
+    Ok(output)
+}
+
+/// Renders math, or the source of the math if it contains an error
+pub fn write_math(output: &mut String, source: &str, display: bool) {
+    match render_math(source, display) {
+        Ok(math) => output.push_str(&math),
+        Err(e) => {
+            warn(&format!(
+                "Math error: {} at {}..{} in {source:?} (\"{}\")",
+                e.message,
+                e.start,
+                e.end,
+                source.get(e.start..e.end).unwrap_or_default()
+            ));
+            output.write_tag("code", source, &[("class", "fxg-math-error")]);
+        }
+    }
+}
+
+/// Parses a math block, started by `<math>` and closed by `</>`
+pub fn parse_math_block<T>(reader: &mut BufReader<T>, output: &mut String)
+where
+    T: Read,
+{
+    let mut source = String::new();
+    let mut lnbuf = String::new();
+    while let Ok(length) = reader.read_line(&mut lnbuf) {
+        if length == 0 || lnbuf.trim() == "</>" {
+            break;
+        }
+        source.push_str(&lnbuf);
+        lnbuf.clear();
+    }
+    write_math(output, source.trim(), true);
+}
@@ -0,0 +1,79 @@
+use crate::{
+    attributes::split_attribute_block,
+    extensions::HtmlWriting,
+    html::is_safe_url,
+    links::write_link,
+    parser::{Context, parse_text},
+    references::resolve_link_reference,
+};
+
+const AUDIO_EXTENSIONS: &[&str] = &["mp3", "ogg", "oga", "opus", "wav", "flac", "m4a", "aac"];
+const VIDEO_EXTENSIONS: &[&str] = &["mp4", "m4v", "webm", "ogv", "mov"];
+
+/// Attributes that can be given to audio and video using an attribute block
+const MEDIA_ATTRIBUTES: &[&str] = &["poster", "width", "height", "preload"];
+
+/// Parses a line that consists of only an embed, `<@file caption>`, optionally followed by an
+/// attribute block. Audio and video files are embedded, everything else becomes a link.
+pub fn parse_media(line: &str, ctx: &Context) -> Option<String> {
+    let (contents, rest) = line.strip_prefix("<@")?.split_once('>')?;
+    let (attributes, rest) = match split_attribute_block(rest) {
+        Some((attributes, rest))
+            if attributes
+                .iter()
+                .all(|(key, _)| MEDIA_ATTRIBUTES.contains(key)) =>
+        {
+            (attributes, rest)
+        }
+        _ => (vec![], rest),
+    };
+    if !rest.trim().is_empty() || contents.is_empty() {
+        return None;
+    }
+    let contents = resolve_link_reference(contents, ctx)?;
+    let (src, caption) = match contents.split_once(' ') {
+        Some((src, caption)) => (src, Some(caption)),
+        None => (contents.as_ref(), None),
+    };
+
+    let extension = src
+        .split(['?', '#'])
+        .next()
+        .and_then(|path| path.rsplit_once('.'))
+        .map(|(_, extension)| extension.to_lowercase())
+        .unwrap_or_default();
+    // embeds that are not allowed are rendered as a link, which leaves the URL out
+    let playable = !ctx.options.safe || is_safe_url(src);
+    let tag = if playable && AUDIO_EXTENSIONS.contains(&extension.as_str()) {
+        "audio"
+    } else if playable && VIDEO_EXTENSIONS.contains(&extension.as_str()) {
+        "video"
+    } else {
+        let mut output = String::new();
+        let text = caption.map_or_else(|| src.to_string(), |caption| parse_text(caption, ctx));
+        write_link(&mut output, src, &text, &[], ctx);
+        return Some(output);
+    };
+
+    let mut output = String::new();
+    if caption.is_some() {
+        output.write_opening_tag("figure", &[("class", "fxg-media")]);
+    }
+    let mut tag_data = vec![("src", src), ("controls", "")];
+    // audio has nothing to show a poster or size on
+    tag_data.extend(
+        attributes
+            .iter()
+            .filter(|(key, _)| tag == "video" || *key == "preload")
+            .filter(|(key, value)| *key != "poster" || !ctx.options.safe || is_safe_url(value)),
+    );
+    output.write_opening_tag(tag, &tag_data);
+    // shown by browsers that can't play the file
+    write_link(&mut output, src, src, &[], ctx);
+    output.write_closing_tag(tag);
+    if let Some(caption) = caption {
+        output.write_tag("figcaption", &parse_text(caption, ctx), &[]);
+        output.write_closing_tag("figure");
+    }
+    Some(output)
+}
@@ -3,12 +3,48 @@
 // TODO: general cleanup & optimize
 
 use std::{
-    collections::HashSet,
+    cell::{Cell, RefCell},
+    collections::{HashMap, HashSet},
     io::{BufRead, BufReader, Read},
-    pin,
+    ops::RangeInclusive,
+    path::PathBuf,
 };
 
-use crate::{UNICODE_PLACEHOLDERS, blockqoutes::parse_blockqoute, escape, extensions::HtmlWriting};
+use crate::{
+    UNICODE_PLACEHOLDERS,
+    abbreviations::{collect_abbreviations, parse_abbreviation_definition, wrap_abbreviations},
+    admonitions::{parse_admonition, parse_admonition_options},
+    attributes::{find_span, replace_id, reserve_id, split_generic_attributes},
+    autolinks::find_autolink,
+    blockqoutes::parse_blockqoute,
+    codeblocks::{CodeblockOptions, parse_codeblock},
+    comments::{parse_comment_block, parse_line_comment, write_comment},
+    definitions::parse_definition_list,
+    details::{parse_details, parse_details_options},
+    directives::{Directive, parse_directive, warn_options},
+    emoji::replace_shortcodes,
+    escape,
+    extensions::HtmlWriting,
+    footnotes::{
+        FOOTNOTE_PLACEHOLDER, is_footnote_name, parse_footnote_definition, write_footnotes,
+    },
+    headings::{
+        CROSS_REFERENCE_PLACEHOLDER, Heading, close_sections, parse_title, write_cross_references,
+    },
+    html::parse_html_block,
+    images::{parse_figure, split_image_attributes, write_image},
+    links::{split_link_attributes, write_link},
+    math::{parse_math_block, write_math},
+    media::parse_media,
+    references::{collect_link_definitions, parse_link_definition, resolve_link_reference},
+    toc::{Toc, TocLevels, parse_toc_options, render_full_toc, toc_placeholder, write_tocs},
+    typography::smarten,
+    verse::parse_verse,
+    warn,
+};
+
+/// Private use characters that are used as placeholders while rendering
+const PLACEHOLDERS: RangeInclusive<char> = '\u{E000}'..='\u{E01F}';
 
 const NUMERICS: &[char] = &['0', '1', '2', '3', '4', '5', '6', '7', '8', '9'];
 
@@ -24,6 +60,19 @@ const UPPERCASE_LETTERS: &[char] = &[
 const ROMAN_NUMERALS_UPPERCASE: &[char] = &['I', 'V', 'X', 'L', 'D', 'M'];
 const ROMAN_NUMERALS_LOWERCASE: &[char] = &['i', 'v', 'x', 'l', 'd', 'm'];
 
+/// Inline markers that wrap text in an HTML tag, i.e. `//cursive//`
+const INLINE_MARKUP: &[(&str, &str)] = &[
+    ("//", "em"),
+    ("!!", "strong"),
+    ("??", "small"),
+    ("__", "u"),
+    ("~~", "del"),
+    ("%%", "mark"),
+    ("^^", "sup"),
+    (",,", "sub"),
+    ("@@", "kbd"),
+];
+
 #[derive(Debug, Clone, Copy)]
 enum OrderedListMarker {
     Numerical,
@@ -60,15 +109,167 @@ fn string_consists_of(input: &str, set: &[char]) -> bool {
     input.chars().all(|c| set.contains(&c))
 }
 
-pub fn parse<T>(reader: &mut BufReader<T>) -> String
+/// Settings that change how a document is rendered
+#[derive(Debug, Default, Clone)]
+pub struct Options {
+    /// Directory that relative paths inside of the document are resolved against
+    pub base_dir: PathBuf,
+    /// Render a link to the heading itself next to every heading
+    pub permalinks: bool,
+    /// Put a table of contents at the start of the document
+    pub toc: Option<TocLevels>,
+    /// Number h2 through h6 like `2.3.1`
+    pub number_headings: bool,
+    /// Wrap every heading and the content below it in a `<section>`
+    pub sections: bool,
+    /// Host of the site the document is published on. Links to other hosts are off-site.
+    pub site_host: Option<String>,
+    /// Add `rel="nofollow"` to every off-site link
+    pub nofollow: bool,
+    /// Class that is added to every off-site link
+    pub external_class: Option<String>,
+    /// Turn bare URLs and email addresses into links
+    pub autolink: bool,
+    /// Only keep allowed tags and attributes in raw HTML blocks
+    pub sanitize_html: bool,
+    /// Use typographic quotes, dashes and ellipses in text
+    pub smart_typography: bool,
+    /// Language of the document, which decides the kind of quotes that are used
+    pub lang: Option<String>,
+    /// Replace shortcodes like `:tada:` with emoji
+    pub emoji: bool,
+    /// Write comments to the output as HTML comments, instead of leaving them out
+    pub keep_comments: bool,
+    /// Render untrusted input: only URLs with a safe scheme, no raw HTML or includes, and
+    /// `rel="nofollow ugc"` on off-site links
+    pub safe: bool,
+}
+
+/// State that is shared between all parts of a document while it is being parsed
+#[derive(Debug)]
+pub struct Context<'a> {
+    pub options: &'a Options,
+    pub codeblocks: usize,
+    pub headings: Vec<Heading>,
+    pub section_numbers: [usize; 5],
+    /// Levels of the headings whose `<section>` has not been closed yet
+    pub open_sections: Vec<usize>,
+    pub tocs: Vec<Toc>,
+    /// Rendered text of every footnote definition, by name
+    pub footnotes: HashMap<String, String>,
+    /// URLs of all link definitions in the document, by name
+    pub links: HashMap<String, String>,
+    /// Abbreviations and their definitions, longest first
+    pub abbreviations: Vec<(String, String)>,
+    /// Whether the text that is being parsed is already inside of a link
+    pub in_link: Cell<bool>,
+    /// Whether every line of a paragraph ends in a line break, i.e. inside of a `<verse>` block
+    pub preserve_line_breaks: bool,
+    ids: RefCell<HashSet<String>>,
+}
+
+impl<'a> Context<'a> {
+    pub fn new(options: &'a Options) -> Self {
+        Self {
+            options,
+            codeblocks: 0,
+            headings: vec![],
+            section_numbers: [0; 5],
+            open_sections: vec![],
+            tocs: vec![],
+            footnotes: HashMap::new(),
+            links: HashMap::new(),
+            abbreviations: vec![],
+            in_link: Cell::new(false),
+            preserve_line_breaks: false,
+            ids: RefCell::new(HashSet::new()),
+        }
+    }
+
+    /// Reserves an ID for an element, appending a number to it if it was already taken
+    pub fn unique_id(&self, id: &str) -> String {
+        let mut ids = self.ids.borrow_mut();
+        if ids.insert(id.to_string()) {
+            return id.to_string();
+        }
+        (1..)
+            .map(|n| format!("{id}-{n}"))
+            .find(|id| ids.insert(id.clone()))
+            .unwrap()
+    }
+
+    /// Reserves an ID that was written by hand, warning if it was already taken
+    pub fn explicit_id(&self, id: &str) -> String {
+        if self.ids.borrow().contains(id) {
+            warn(&format!("ID {id} is used more than once, renaming..."));
+        }
+        self.unique_id(id)
+    }
+}
+
+pub fn parse_with_options<T>(reader: &mut BufReader<T>, options: &Options) -> String
 where
     T: std::io::Read,
 {
-    let mut output = String::new();
-    let mut lnbuf = String::new();
+    let mut ctx = Context::new(options);
+
+    // some parts of the document can be used before they are defined, so those are collected
+    // before anything is rendered
+    let mut source = String::new();
+    if let Err(e) = reader.read_to_string(&mut source) {
+        warn(&format!("Input wasn't able to be read ({e})"));
+    }
+    // the placeholders are turned into markup at the end, so they can't be part of the input
+    source.retain(|c| !PLACEHOLDERS.contains(&c));
+    ctx.links = collect_link_definitions(&source);
+    ctx.abbreviations = collect_abbreviations(&source);
 
     // output.write_opening_tag("div", &[("class", "fxg-content")]);
 
+    let mut output = parse_blocks(&mut BufReader::new(source.as_bytes()), &mut ctx);
+
+    close_sections(&mut output, 1, &mut ctx);
+    output = write_footnotes(output, &ctx);
+
+    // output.write_closing_tag("div");
+
+    output = write_tocs(output, &ctx);
+    if let Some(levels) = options.toc {
+        output.insert_str(0, &render_full_toc(levels, &ctx));
+    }
+    // headings in tables of contents can contain cross-references too
+    output = write_cross_references(output, &ctx);
+
+    output = output
+        .replace("&", "&amp;")
+        .replace("<", "&lt;")
+        .replace(">", "&gt;");
+    //.replace("\"", "&qout;");
+
+    for (key, placeholder) in UNICODE_PLACEHOLDERS.entries() {
+        if *key == "</>" {
+            output = output.replace(placeholder, "&lt;/&gt;");
+        } else {
+            output = output.replace(placeholder, key);
+        }
+    }
+
+    output
+}
+
+/// Renders the body of a block, i.e. an admonition, as a document of its own. Sections that are
+/// opened inside of the block are also closed inside of it.
+pub fn parse_nested(source: &str, ctx: &mut Context) -> String {
+    let open_sections = std::mem::take(&mut ctx.open_sections);
+    let mut output = parse_blocks(&mut BufReader::new(source.as_bytes()), ctx);
+    close_sections(&mut output, 1, ctx);
+    ctx.open_sections = open_sections;
+    output
+}
+
+fn parse_blocks(reader: &mut BufReader<&[u8]>, ctx: &mut Context) -> String {
+    let mut output = String::new();
+    let mut lnbuf = String::new();
     let mut last_line_was_title = false;
 
     while let Ok(n) = reader.read_line(&mut lnbuf) {
@@ -77,8 +278,10 @@ where
             // EOF reached
             break;
         }
-        if lnbuf.starts_with('=') {
-            output.push_str(&parse_title(line));
+        if let Some(comment) = parse_line_comment(line) {
+            write_comment(&mut output, comment, ctx);
+        } else if lnbuf.starts_with('=') {
+            output.push_str(&parse_title(line, ctx));
             last_line_was_title = true;
         } else if line.is_empty() && !last_line_was_title {
             output.push_str(escape!("<"));
@@ -86,7 +289,7 @@ where
             output.push_str(escape!(">"));
         } else if lnbuf.starts_with('-') {
             if &lnbuf[1..2] == " " {
-                output.push_str(&parse_ul(reader, lnbuf.clone()));
+                output.push_str(&parse_ul(reader, lnbuf.clone(), ctx));
                 lnbuf.clear();
                 continue;
             }
@@ -96,34 +299,78 @@ where
                 output.push_str("hr");
                 output.push_str(escape!(">"));
             } else {
-                output.push_str(&parse_text(line));
-                output.push(' ');
+                output.push_str(&parse_text_line(line, ctx));
                 last_line_was_title = false;
             }
         } else if lnbuf.starts_with('>') {
-            let (blockqoute, _) = parse_blockqoute(reader, lnbuf.clone()); // ? elegance
+            let (blockqoute, _) = parse_blockqoute(reader, lnbuf.clone(), ctx); // ? elegance
             output.push_str(&blockqoute);
             last_line_was_title = false;
-        } else if line.starts_with('<')
-            && line.ends_with('>')
-            && line[1..line.len() - 1].chars().all(char::is_alphabetic)
-        {
-            parse_codeblock(reader, &mut output, lnbuf.clone(), line);
-        } else if lnbuf.starts_with('|') && line.ends_with('|') {
-            let mut table = String::new();
-            while let Ok(length) = reader.read_line(&mut lnbuf) {
-                if length == 0 {
-                    break;
+        } else if lnbuf.starts_with("; ") {
+            output.push_str(&parse_definition_list(reader, lnbuf.clone(), ctx));
+            last_line_was_title = false;
+        } else if let Some(figure) = parse_figure(line, ctx) {
+            output.push_str(&figure);
+            last_line_was_title = false;
+        } else if let Some(media) = parse_media(line, ctx) {
+            output.push_str(&media);
+            last_line_was_title = false;
+        } else if parse_link_definition(line).is_some() {
+            // link definitions are collected before the document is rendered
+        } else if parse_abbreviation_definition(line).is_some() {
+            // abbreviations are collected before the document is rendered
+        } else if parse_footnote_definition(line, ctx) {
+            // footnotes are rendered at the end of the document
+        } else if let Some((Directive::Toc, _, info)) = parse_directive(line) {
+            output.push_str(&toc_placeholder(parse_toc_options(info), ctx));
+        } else if let Some((directive, name, info)) = parse_directive(line) {
+            match directive {
+                Directive::Admonition => {
+                    let title = parse_admonition_options(name, info);
+                    parse_admonition(reader, &mut output, name, title, ctx);
                 }
-                let line = &lnbuf[lnbuf.len() - length..lnbuf.len()].trim();
-                if line.starts_with('|') && line.ends_with('|') {
-                    table.push_str(&lnbuf);
-                } else {
-                    break;
+                Directive::Details => {
+                    let options = parse_details_options(info);
+                    parse_details(reader, &mut output, &options, ctx);
                 }
-                lnbuf.clear();
+                Directive::Verse => {
+                    warn_options(name, info);
+                    parse_verse(reader, &mut output, ctx);
+                }
+                Directive::Comment => {
+                    warn_options(name, info);
+                    parse_comment_block(reader, &mut output, ctx);
+                }
+                Directive::Html => {
+                    warn_options(name, info);
+                    parse_html_block(reader, &mut output, ctx);
+                }
+                Directive::Math => {
+                    warn_options(name, info);
+                    parse_math_block(reader, &mut output);
+                }
+                Directive::Toc => unreachable!(),
             }
-            output.push_str(&parse_table(&table));
+            last_line_was_title = false;
+        } else if let Some(options) = line
+            .strip_prefix('<')
+            .and_then(|line| line.strip_suffix('>'))
+            .and_then(CodeblockOptions::parse)
+        {
+            parse_codeblock(reader, &mut output, lnbuf.clone(), &options, ctx);
+        } else if lnbuf.starts_with('|') && line.ends_with('|') {
+            let table = read_table(reader, &mut lnbuf);
+            output.push_str(&parse_table(&table, &[], ctx));
+        } else if split_generic_attributes(line).is_some_and(|(_, rest)| rest.is_empty())
+            && next_line_is_table_row(reader)
+        {
+            // an attribute block on the line above a table belongs to the table
+            let attribute_line = line.to_string();
+            let (attributes, _) = split_generic_attributes(&attribute_line).unwrap();
+            lnbuf.clear();
+            let _ = reader.read_line(&mut lnbuf);
+            let table = read_table(reader, &mut lnbuf);
+            output.push_str(&parse_table(&table, &attributes, ctx));
         } else {
             if let Some(pos) = lnbuf.find('.') {
                 let marker = &lnbuf[..pos];
@@ -163,98 +410,44 @@ where
                         1,
                     ));
                 } else {
-                    output.push_str(&parse_text(line));
-                    output.push(' ');
+                    output.push_str(&parse_text_line(line, ctx));
                     last_line_was_title = false;
                 }
             } else {
-                output.push_str(&parse_text(line));
-                output.push(' ');
+                output.push_str(&parse_text_line(line, ctx));
                 last_line_was_title = false;
             }
         }
         lnbuf.clear();
     }
 
-    // output.write_closing_tag("div");
-
-    output = output
-        .replace("&", "&amp;")
-        .replace("<", "&lt;")
-        .replace(">", "&gt;");
-    //.replace("\"", "&qout;");
-
-    for (key, placeholder) in UNICODE_PLACEHOLDERS.entries() {
-        if *key == "</>" {
-            output = output.replace(placeholder, "&lt;/&gt;");
-        } else {
-            output = output.replace(placeholder, key);
-        }
-    }
-
     output
 }
 
-fn parse_codeblock<T>(reader: &mut BufReader<T>, output: &mut String, mut lnbuf: String, line: &str)
-where
-    T: Read,
-{
-    let lang = &line[1..line.len() - 1];
-    if lang.is_empty() {
-        output.write_opening_tag("pre", &[]);
-        output.write_opening_tag("code", &[]);
-    } else {
-        output.write_opening_tag("pre", &[]);
-        output.write_opening_tag("code", &[("class", &format!("language-{lang}"))]);
-    }
-    lnbuf.clear();
-    while let Ok(length) = reader.read_line(&mut lnbuf) {
-        if length == 0 {
-            break;
-        }
-        if &lnbuf[lnbuf.len() - length..lnbuf.len() - 1] == "</>" {
-            break;
-        }
-    }
-
-    let least_indent = lnbuf
-        .lines()
-        .rev()
-        .skip(1)
-        .map(|line| line.find(|c: char| !c.is_whitespace()))
-        .filter(|x| x.is_some())
-        .map(|x| x.unwrap())
-        .min()
-        .unwrap();
-
-    let mut lines = lnbuf.lines().peekable();
-    while let Some(line) = lines.next() {
-        if let None = lines.peek() {
-            break;
-        }
-        if line.is_empty() {
-            output.push('\n');
-        } else {
-            output.push_str(&line[least_indent..]);
-            output.push('\n');
-        }
-    }
-    output.write_closing_tag("code");
-    output.write_closing_tag("pre");
+/// Finds the first marker in a line, skipping the `//` in URLs like `https://`
+fn find_markup(line: &str, markup: &str) -> Option<usize> {
+    line.match_indices(markup)
+        .map(|(idx, _)| idx)
+        .find(|idx| markup != "//" || !line[..*idx].ends_with(':'))
 }
 
-fn parse_markup(input: &str, markup: &'static str, html_tag: &'static str) -> String {
+fn parse_markup(
+    input: &str,
+    markup: &'static str,
+    html_tag: &'static str,
+    ctx: &Context,
+) -> String {
     let mut line = input.to_string();
     let mut output = String::new();
     let mut found = false;
     let mut tag_contents = "";
     let mut tag_remainder = "";
     while let Some(idx) = line.find(markup) {
-        found = true;
-        if &input[idx - 1..idx] == "\\" {
+        if idx > 0 && line[..idx].ends_with('\\') {
             line = line.replacen(&format!("\\{markup}"), escape!(markup), 1); // ? can we remove this format! call
-            found = false;
         } else {
+            // markers without anything between them, i.e. `,,,,`, are left as they are
+            found = idx > 0;
             tag_contents = &line[..idx];
             tag_remainder = &line[idx + markup.len()..];
             break;
@@ -265,164 +458,187 @@ fn parse_markup(input: &str, markup: &'static str, html_tag: &'static str) -> St
         output.push_str(escape!("<"));
         output.push_str(html_tag);
         output.push_str(escape!(">"));
-        output.push_str(&parse_text(tag_contents));
+        output.push_str(&parse_text(tag_contents, ctx));
         output.push_str(escape!("<"));
-        output.push_str("/");
+        output.push('/');
         output.push_str(html_tag);
         output.push_str(escape!(">"));
-        output.push_str(&parse_text(tag_remainder));
+        output.push_str(&parse_text(tag_remainder, ctx));
     } else {
         // insert the text without further markup
         output.push_str(markup);
-        output.push_str(&parse_text(&line));
+        output.push_str(&parse_text(&line, ctx));
     }
 
     output
 }
 
-fn parse_title(line: &str) -> String {
-    let mut output = String::new();
-    let (prefix, _) = line.split_once(' ').unwrap();
-    let header_size = prefix.len();
-    // tags above <h6> don't exist
-    if header_size > 6 {
-        output.push_str(&parse_text(line));
-        return output;
-    }
-
-    if line.ends_with(&str::repeat("=", header_size)) {
-        let header_contents = &line[header_size..line.len() - 1 - header_size].trim();
-        output.push_str(escape!("<"));
-        output.push('h');
-        output.push_str(&header_size.to_string());
-        output.push_str(escape!(">"));
-        output.push_str(&parse_text(header_contents));
-        output.push_str(escape!("<"));
-        output.push_str("/h");
-        output.push_str(&header_size.to_string());
-        output.push_str(escape!(">"));
+/// Parses a line of a paragraph. Lines are joined by a space, unless the line ends in a `\` or
+/// line breaks are preserved, in which case they are joined by a line break.
+fn parse_text_line(line: &str, ctx: &Context) -> String {
+    let (line, hard_break) = match line.strip_suffix('\\') {
+        // an escaped backslash, `\\`, is a single backslash and not a line break
+        Some(line) if line.ends_with('\\') => (line, ctx.preserve_line_breaks),
+        Some(line) => (line.trim_end(), true),
+        None => (line, ctx.preserve_line_breaks),
+    };
+    let mut output = parse_text(line, ctx);
+    if hard_break {
+        output.write_opening_tag("br", &[]);
     } else {
-        // parse the text normally
-        output.push_str(&parse_text(line));
+        output.push(' ');
     }
     output
 }
 
-pub fn parse_text(line: &str) -> String {
+/// Runs the passes that change plain text, which is text outside of markup, code and links
+fn render_text(text: &str, ctx: &Context) -> String {
+    let text = replace_shortcodes(text, ctx);
+    // abbreviations are matched before quotes and dashes are changed, as they are defined with
+    // straight quotes
+    let text = wrap_abbreviations(&text, ctx);
+    smarten(&text, ctx).into_owned()
+}
+
+pub fn parse_text(line: &str, ctx: &Context) -> String {
     let mut output = String::new();
     // find opening tag
-    let cursive = line.find("//");
-    let small = line.find("??");
-    let bold = line.find("!!");
-    let underline = line.find("__");
+    let markup = INLINE_MARKUP
+        .iter()
+        .filter_map(|(markup, tag)| find_markup(line, markup).map(|idx| (idx, *markup, *tag)))
+        .min_by_key(|(idx, _, _)| *idx);
     let code = line.find("<>");
     let link = line.find("<#");
     let image = line.find("<!");
-    let smallest = [cursive, bold, underline, code, link, image, small]
-        .iter()
-        .filter(|x| x.is_some())
-        .map(|x| x.unwrap())
-        .min();
-    if smallest.is_none() {
-        return line.to_string();
-    } else {
-        // ? lot of repeating here,, see if more efficient way is possible
-        if smallest == cursive {
-            let idx = smallest.unwrap();
-            let text = &line[idx + 2..];
-            output.push_str(&line[..idx]);
-            output.push_str(&parse_markup(text, "//", "em"));
-        } else if smallest == bold {
-            let idx = smallest.unwrap();
-            let text = &line[idx + 2..];
-            output.push_str(&line[..idx]);
-            output.push_str(&parse_markup(text, "!!", "strong"));
-        } else if smallest == small {
-            let idx = smallest.unwrap();
-            let text = &line[idx + 2..];
-            output.push_str(&line[..idx]);
-            output.push_str(&parse_markup(text, "??", "small"));
-        } else if smallest == underline {
-            let idx = smallest.unwrap();
-            let text = &line[idx + 2..];
-            output.push_str(&line[..idx]);
-            output.push_str(&parse_markup(text, "__", "u"));
-        } else if smallest == code {
-            let idx = smallest.unwrap();
-            let text = &line[idx + 2..];
-            output.push_str(&line[..idx]);
-            output.push_str(&parse_code(text));
-        } else if smallest == link {
-            let idx = smallest.unwrap();
-            output.push_str(&line[..idx]);
-            let data = &line[idx + 2..];
-            if let Some(idx_end) = data.find(">") {
-                let contents = &data[..idx_end];
-                if let Some((link, desc)) = contents.split_once(" ") {
-                    output.push_str(escape!("<"));
-                    output.push_str("a href=");
-                    output.push_str(escape!("\""));
-                    output.push_str(link);
-                    output.push_str(escape!("\""));
-                    output.push_str(escape!(">"));
-                    output.push_str(&parse_text(desc));
-                    output.push_str(escape!("<"));
-                    output.push_str("/a");
-                    output.push_str(escape!(">"));
-                    output.push_str(&parse_text(&data[idx_end + 1..]));
-                } else {
-                    output.push_str(escape!("<"));
-                    output.push_str("a href=");
-                    output.push_str(escape!("\""));
-                    output.push_str(contents);
-                    output.push_str(escape!("\""));
-                    output.push_str(escape!(">"));
-                    output.push_str(contents);
-                    output.push_str(escape!("<"));
-                    output.push_str("/a");
-                    output.push_str(escape!(">"));
-                    output.push_str(&parse_text(&data[idx_end + 1..]));
-                }
-            } else {
+    let footnote = line.find("<^");
+    let math = line.find("$$");
+    let autolink = (ctx.options.autolink && !ctx.in_link.get())
+        .then(|| find_autolink(line))
+        .flatten();
+    let bare_link = autolink.as_ref().map(|autolink| autolink.start);
+    let span = find_span(line);
+    let Some(idx) = [
+        markup.map(|(idx, _, _)| idx),
+        code,
+        link,
+        image,
+        footnote,
+        math,
+        bare_link,
+        span.as_ref().map(|span| span.start),
+    ]
+    .into_iter()
+    .flatten()
+    .min() else {
+        return render_text(line, ctx);
+    };
+    let smallest = Some(idx);
+    output.push_str(&render_text(&line[..idx], ctx));
+    // ? lot of repeating here,, see if more efficient way is possible
+    if let Some((_, markup, html_tag)) = markup.filter(|(markup_idx, _, _)| *markup_idx == idx) {
+        let text = &line[idx + markup.len()..];
+        output.push_str(&parse_markup(text, markup, html_tag, ctx));
+    } else if smallest == code {
+        let text = &line[idx + 2..];
+        output.push_str(&parse_code(text, ctx));
+    } else if smallest == link {
+        let data = &line[idx + 2..];
+        if let Some(idx_end) = data.find(">") {
+            let Some(contents) = resolve_link_reference(&data[..idx_end], ctx) else {
                 output.push_str("<#");
-                output.push_str(&parse_text(&line[idx + 2..]));
-            }
-        } else if smallest == image {
-            let idx = smallest.unwrap();
-            output.push_str(&line[..idx]);
-            let data = &line[idx + 2..];
-            if let Some(idx_end) = data.find(">") {
-                let contents = &data[..idx_end];
-                if let Some((link, alt)) = contents.split_once(" ") {
-                    output.push_str(escape!("<"));
-                    output.push_str("img src=");
-                    output.push_str(escape!("\""));
-                    output.push_str(link);
-                    output.push_str(escape!("\""));
-                    output.push_str(" alt=");
-                    output.push_str(escape!("\""));
-                    output.push_str(alt);
-                    output.push_str(escape!("\""));
-                    output.push_str(escape!(">"));
-                    output.push_str(&parse_text(&data[idx_end + 1..]));
-                } else {
-                    output.push_str(escape!("<"));
-                    output.push_str("img src=");
-                    output.push_str(escape!("\""));
-                    output.push_str(contents);
-                    output.push_str(escape!("\""));
-                    output.push_str(escape!(">"));
-                }
+                output.push_str(&parse_text(data, ctx));
+                return output;
+            };
+            let contents = contents.as_ref();
+            let (attributes, remainder) = split_link_attributes(&data[idx_end + 1..]);
+            if let Some((link, desc)) = contents.split_once(" ") {
+                // links can't contain other links
+                ctx.in_link.set(true);
+                let desc = parse_text(desc, ctx);
+                ctx.in_link.set(false);
+                write_link(&mut output, link, &desc, &attributes, ctx);
+            } else if let Some(id) = contents.strip_prefix('#').filter(|id| !id.is_empty()) {
+                // a link to a heading without text is a cross-reference
+                let reference =
+                    format!("{CROSS_REFERENCE_PLACEHOLDER}{id}{CROSS_REFERENCE_PLACEHOLDER}");
+                write_link(&mut output, contents, &reference, &attributes, ctx);
             } else {
+                write_link(&mut output, contents, contents, &attributes, ctx);
+            }
+            output.push_str(&parse_text(remainder, ctx));
+        } else {
+            output.push_str("<#");
+            output.push_str(&parse_text(&line[idx + 2..], ctx));
+        }
+    } else if smallest == image {
+        let data = &line[idx + 2..];
+        if let Some(idx_end) = data.find(">") {
+            let Some(contents) = resolve_link_reference(&data[..idx_end], ctx) else {
                 output.push_str("<!");
-                output.push_str(&parse_text(&line[idx + 2..]));
+                output.push_str(&parse_text(data, ctx));
+                return output;
+            };
+            let contents = contents.as_ref();
+            let (attributes, remainder) = split_image_attributes(&data[idx_end + 1..]);
+            if attributes.iter().any(|(key, _)| *key == "caption") {
+                warn(
+                    "Images with a caption have to be on a line of their own, ignoring caption...",
+                );
+            }
+            match contents.split_once(" ") {
+                Some((link, alt)) => write_image(&mut output, link, Some(alt), &attributes, ctx),
+                None => write_image(&mut output, contents, None, &attributes, ctx),
+            }
+            output.push_str(&parse_text(remainder, ctx));
+        } else {
+            output.push_str("<!");
+            output.push_str(&parse_text(&line[idx + 2..], ctx));
+        }
+    } else if smallest == bare_link {
+        let autolink = autolink.unwrap();
+        let text = &line[autolink.start..autolink.end];
+        write_link(&mut output, &autolink.href, text, &[], ctx);
+        output.push_str(&parse_text(&line[autolink.end..], ctx));
+    } else if smallest == span.as_ref().map(|span| span.start) {
+        let span = span.unwrap();
+        let id = reserve_id(&span.attributes, ctx);
+        output.write_tag(
+            "span",
+            &parse_text(span.contents, ctx),
+            &replace_id(&span.attributes, id.as_deref()),
+        );
+        output.push_str(&parse_text(&line[span.end..], ctx));
+    } else if smallest == math {
+        let data = &line[idx + 2..];
+        match data.split_once("$$") {
+            Some((source, remainder)) => {
+                write_math(&mut output, source.trim(), false);
+                output.push_str(&parse_text(remainder, ctx));
+            }
+            None => {
+                output.push_str("$$");
+                output.push_str(&parse_text(data, ctx));
+            }
+        }
+    } else if smallest == footnote {
+        let data = &line[idx + 2..];
+        match data.split_once('>') {
+            Some((name, remainder)) if is_footnote_name(name) => {
+                output.push(FOOTNOTE_PLACEHOLDER);
+                output.push_str(name);
+                output.push(FOOTNOTE_PLACEHOLDER);
+                output.push_str(&parse_text(remainder, ctx));
+            }
+            _ => {
+                output.push_str("<^");
+                output.push_str(&parse_text(data, ctx));
             }
         }
     }
     output
 }
 
-fn parse_code(line_dat: &str) -> String {
+fn parse_code(line_dat: &str, ctx: &Context) -> String {
     let mut output = String::new();
     let mut line = line_dat.to_string();
     let mut tag_contents = "";
@@ -456,13 +672,53 @@ fn parse_code(line_dat: &str) -> String {
     output.push_str(escape!("<"));
     output.push_str("/code");
     output.push_str(escape!(">"));
-    output.push_str(&parse_text(tag_remainder));
+    output.push_str(&parse_text(tag_remainder, ctx));
     output
 }
 
-fn parse_table(table: &str) -> String {
+/// Reads the rows of a table, starting with the row in `lnbuf`
+fn read_table<T>(reader: &mut BufReader<T>, lnbuf: &mut String) -> String
+where
+    T: Read,
+{
+    let mut table = String::new();
+    while let Ok(length) = reader.read_line(lnbuf) {
+        if length == 0 {
+            break;
+        }
+        let line = &lnbuf[lnbuf.len() - length..lnbuf.len()].trim();
+        if line.starts_with('|') && line.ends_with('|') {
+            table.push_str(lnbuf);
+        } else {
+            break;
+        }
+        lnbuf.clear();
+    }
+    table
+}
+
+/// Returns the next line without reading it. The input is already in memory, so the whole line
+/// is returned even if only part of it is in the reader's buffer.
+pub fn peek_line(reader: &BufReader<&[u8]>) -> String {
+    let line = reader
+        .buffer()
+        .iter()
+        .chain(reader.get_ref().iter())
+        .take_while(|c| **c != b'\n')
+        .copied()
+        .collect::<Vec<_>>();
+    String::from_utf8_lossy(&line).into_owned()
+}
+
+/// Checks whether the next line starts a table, without reading it
+fn next_line_is_table_row(reader: &BufReader<&[u8]>) -> bool {
+    let line = peek_line(reader);
+    let line = line.trim_end();
+    line.starts_with('|') && line.ends_with('|')
+}
+
+fn parse_table(table: &str, attributes: &[(&str, &str)], ctx: &Context) -> String {
     let mut output = String::new();
-    output.write_opening_tag("table", &[]);
     let mut is_header = true;
     let mut last_was_header = true;
     let mut rowbuf = String::new();
@@ -499,12 +755,17 @@ fn parse_table(table: &str) -> String {
     if is_header {
         output = output.replace("th", "td"); // all is data
     }
+    // written last, so the replacement above can't touch the attributes
+    let mut table_tag = String::new();
+    let id = reserve_id(attributes, ctx);
+    table_tag.write_opening_tag("table", &replace_id(attributes, id.as_deref()));
+    output.insert_str(0, &table_tag);
     output.write_closing_tag("table");
 
     output
 }
 
-fn parse_ul<T>(reader: &mut BufReader<T>, mut lnbuf: String) -> String
+fn parse_ul<T>(reader: &mut BufReader<T>, mut lnbuf: String, ctx: &Context) -> String
 where
     T: Read,
 {
@@ -538,7 +799,7 @@ where
             }
             current_depth = depth;
         }
-        output.write_tag("li", &parse_li(&item[pre..]), &[]);
+        output.write_tag("li", &parse_li(&item[pre..], ctx), &[]);
     }
     for _ in 0..current_depth {
         output.write_closing_tag("ul");
@@ -575,15 +836,16 @@ where
             } else {
                 if let Some((potential_indexer, _)) = item.split_once('.') {
                     let indexer = potential_indexer.trim();
-                    if string_consists_of(indexer, NUMERICS) {
-                        items.push(parse_ol(reader, buffer.clone(), marker, depth + 1));
-                    } else if string_consists_of(indexer, ROMAN_NUMERALS_UPPERCASE) {
-                        items.push(parse_ol(reader, buffer.clone(), marker, depth + 1));
-                    } else if string_consists_of(indexer, ROMAN_NUMERALS_LOWERCASE) {
-                        items.push(parse_ol(reader, buffer.clone(), marker, depth + 1));
-                    } else if string_consists_of(indexer, LOWERCASE_LETTERS) {
-                        items.push(parse_ol(reader, buffer.clone(), marker, depth + 1));
-                    } else if string_consists_of(indexer, UPPERCASE_LETTERS) {
+                    if [
+                        NUMERICS,
+                        ROMAN_NUMERALS_UPPERCASE,
+                        ROMAN_NUMERALS_LOWERCASE,
+                        LOWERCASE_LETTERS,
+                        UPPERCASE_LETTERS,
+                    ]
+                    .iter()
+                    .any(|set| string_consists_of(indexer, set))
+                    {
                         items.push(parse_ol(reader, buffer.clone(), marker, depth + 1));
                     }
                 }
@@ -604,7 +866,7 @@ where
     output
 }
 
-fn parse_li(rli: &str) -> String {
+fn parse_li(rli: &str, ctx: &Context) -> String {
     let li = rli.trim();
     let prefix = &li[..3];
     match prefix {
@@ -612,7 +874,7 @@ fn parse_li(rli: &str) -> String {
             let mut output = String::new();
             output.write_tag(
                 "input",
-                &parse_text(&li[3..]),
+                &parse_text(&li[3..], ctx),
                 &[("type", "checkbox"), ("class", "fxg-clitem")],
             );
             output
@@ -621,7 +883,7 @@ fn parse_li(rli: &str) -> String {
             let mut output = String::new();
             output.write_tag(
                 "input",
-                &parse_text(&li[3..]),
+                &parse_text(&li[3..], ctx),
                 &[
                     ("type", "checkbox"),
                     ("class", "fxg-indeterminate fxg-clitem"),
@@ -633,7 +895,7 @@ fn parse_li(rli: &str) -> String {
             let mut output = String::new();
             output.write_tag(
                 "input",
-                &parse_text(&li[3..]),
+                &parse_text(&li[3..], ctx),
                 &[
                     ("type", "checkbox"),
                     ("class", "fxg-indeterminate fxg-clitem"),
@@ -642,6 +904,6 @@ fn parse_li(rli: &str) -> String {
             );
             output
         }
-        _ => parse_text(li),
+        _ => parse_text(li, ctx),
     }
 }
@@ -0,0 +1,58 @@
+use std::{borrow::Cow, collections::HashMap};
+
+use crate::{directives::lines_outside_raw_bodies, parser::Context, warn};
+
+#[inline(always)]
+fn is_reference_name(name: &str) -> bool {
+    !name.is_empty() && !name.contains(|c: char| c.is_whitespace() || c == ']')
+}
+
+/// Parses a link definition line, `[name]: https://example.com/`
+pub fn parse_link_definition(line: &str) -> Option<(&str, &str)> {
+    let (name, url) = line.strip_prefix('[')?.split_once("]:")?;
+    let url = url.trim();
+    if !is_reference_name(name) || url.is_empty() || url.contains(char::is_whitespace) {
+        return None;
+    }
+    Some((name, url))
+}
+
+/// Collects all link definitions in the document, so links can refer to definitions that come
+/// after them
+pub fn collect_link_definitions(source: &str) -> HashMap<String, String> {
+    let mut definitions = HashMap::new();
+    for (name, url) in
+        lines_outside_raw_bodies(source).filter_map(|line| parse_link_definition(line.trim()))
+    {
+        if definitions
+            .insert(name.to_string(), url.to_string())
+            .is_some()
+        {
+            warn(&format!(
+                "Link {name} is defined more than once, using the last definition..."
+            ));
+        }
+    }
+    definitions
+}
+
+/// Replaces a leading `[name]` in the contents of a link or image with the URL it refers to.
+/// Returns `None` if there is no definition for the name.
+pub fn resolve_link_reference<'a>(contents: &'a str, ctx: &Context) -> Option<Cow<'a, str>> {
+    let Some((name, rest)) = contents
+        .strip_prefix('[')
+        .and_then(|contents| contents.split_once(']'))
+        .filter(|(name, rest)| {
+            is_reference_name(name) && (rest.is_empty() || rest.starts_with(' '))
+        })
+    else {
+        return Some(Cow::Borrowed(contents));
+    };
+    match ctx.links.get(name) {
+        Some(url) => Some(Cow::Owned(format!("{url}{rest}"))),
+        None => {
+            warn(&format!("Link {name} is referenced, but never defined"));
+            None
+        }
+    }
+}
@@ -0,0 +1,148 @@
+use crate::{
+    directives::{directive_options, warn_unknown_option},
+    extensions::HtmlWriting,
+    parser::Context,
+    warn,
+};
+
+/// Marks the spot where a table of contents is inserted once all headings are known
+const TOC_PLACEHOLDER: char = '\u{E010}';
+
+/// The heading levels that are included in a table of contents
+#[derive(Debug, Clone, Copy)]
+pub struct TocLevels {
+    pub min: usize,
+    pub max: usize,
+}
+
+impl Default for TocLevels {
+    fn default() -> Self {
+        Self { min: 1, max: 6 }
+    }
+}
+
+/// A table of contents that was requested somewhere in the document
+#[derive(Debug)]
+pub struct Toc {
+    levels: TocLevels,
+    /// Index of the first heading below the table of contents
+    first_heading: usize,
+}
+
+/// Parses a heading level, which goes from 1 to 6
+#[inline(always)]
+pub fn parse_level(level: &str) -> Option<usize> {
+    level.parse().ok().filter(|level| (1..=6).contains(level))
+}
+
+/// Parses the options of a `<toc>` directive, which are `min=` and `max=` levels. Options that
+/// are not valid are ignored with a warning.
+pub fn parse_toc_options(info: &str) -> TocLevels {
+    let mut levels = TocLevels::default();
+    for token in directive_options("toc", info) {
+        let (level, value) = match token.split_once('=') {
+            Some(("min", value)) => (&mut levels.min, value),
+            Some(("max", value)) => (&mut levels.max, value),
+            _ => {
+                warn_unknown_option("toc", token);
+                continue;
+            }
+        };
+        match parse_level(value) {
+            Some(value) => *level = value,
+            None => warn(&format!(
+                "{token} in <toc> is not a heading level (1-6), ignoring it..."
+            )),
+        }
+    }
+    if levels.min > levels.max {
+        warn(&format!(
+            "min={} in <toc> is above max={}, ignoring both...",
+            levels.min, levels.max
+        ));
+        return TocLevels::default();
+    }
+    levels
+}
+
+/// Reserves a table of contents for all headings that come after it, returning the placeholder
+/// that has to be written to the output
+pub fn toc_placeholder(levels: TocLevels, ctx: &mut Context) -> String {
+    ctx.tocs.push(Toc {
+        levels,
+        first_heading: ctx.headings.len(),
+    });
+    format!("{TOC_PLACEHOLDER}{}{TOC_PLACEHOLDER}", ctx.tocs.len() - 1)
+}
+
+/// Replaces all placeholders with their table of contents
+pub fn write_tocs(mut output: String, ctx: &Context) -> String {
+    for (idx, toc) in ctx.tocs.iter().enumerate() {
+        output = output.replacen(
+            &format!("{TOC_PLACEHOLDER}{idx}{TOC_PLACEHOLDER}"),
+            &render_toc(toc, ctx),
+            1,
+        );
+    }
+    output
+}
+
+/// Renders a table of contents for every heading in the document
+pub fn render_full_toc(levels: TocLevels, ctx: &Context) -> String {
+    render_toc(
+        &Toc {
+            levels,
+            first_heading: 0,
+        },
+        ctx,
+    )
+}
+
+fn render_toc(toc: &Toc, ctx: &Context) -> String {
+    let mut output = String::new();
+    output.write_opening_tag("nav", &[("class", "fxg-toc")]);
+    // levels of the lists that are currently open
+    let mut open_lists: Vec<usize> = vec![];
+    for heading in ctx.headings[toc.first_heading..]
+        .iter()
+        .filter(|heading| (toc.levels.min..=toc.levels.max).contains(&heading.level))
+    {
+        let Some(id) = &heading.id else {
+            continue;
+        };
+        match open_lists.last() {
+            Some(_) => {
+                while open_lists.len() > 1 && heading.level < *open_lists.last().unwrap() {
+                    output.write_closing_tag("li");
+                    output.write_closing_tag("ul");
+                    open_lists.pop();
+                }
+                if heading.level > *open_lists.last().unwrap() {
+                    // deeper headings are nested inside of the item that is still open
+                    output.write_opening_tag("ul", &[]);
+                    open_lists.push(heading.level);
+                } else {
+                    output.write_closing_tag("li");
+                }
+            }
+            None => {
+                output.write_opening_tag("ul", &[]);
+                open_lists.push(heading.level);
+            }
+        }
+        output.write_opening_tag("li", &[]);
+        output.write_opening_tag("a", &[("href", &format!("#{id}"))]);
+        if let Some(number) = &heading.number {
+            output.write_tag("span", number, &[("class", "fxg-secnum")]);
+            output.push(' ');
+        }
+        output.push_str(&heading.contents);
+        output.write_closing_tag("a");
+    }
+    while open_lists.pop().is_some() {
+        output.write_closing_tag("li");
+        output.write_closing_tag("ul");
+    }
+    output.write_closing_tag("nav");
+    output
+}
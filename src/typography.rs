@@ -0,0 +1,180 @@
+use std::borrow::Cow;
+
+use phf_macros::phf_map;
+
+use crate::{
+    autolinks::{is_url, url_end},
+    parser::Context,
+};
+
+/// Opening and closing double quotes, followed by opening and closing single quotes
+type Quotes = (char, char, char, char);
+
+/// Quotes by language. Languages that are not listed use English quotes.
+static QUOTES: phf::Map<&'static str, Quotes> = phf_map! {
+    "en" => ('“', '”', '‘', '’'),
+    "nl" => ('“', '”', '‘', '’'),
+    "de" => ('„', '“', '‚', '‘'),
+    "pl" => ('„', '”', '‚', '’'),
+    "sv" => ('”', '”', '’', '’'),
+    "fr" => ('«', '»', '‹', '›'),
+    "es" => ('«', '»', '“', '”'),
+    "it" => ('«', '»', '“', '”'),
+    "ru" => ('«', '»', '„', '“'),
+};
+
+const NO_BREAK_SPACE: char = '\u{A0}';
+const NARROW_NO_BREAK_SPACE: char = '\u{202F}';
+
+/// Turns straight quotes into typographic quotes, `--` and `---` into en and em dashes and `...`
+/// into an ellipsis, if the `smart_typography` option is set. URLs are left as they are.
+pub fn smarten<'a>(text: &'a str, ctx: &Context) -> Cow<'a, str> {
+    if !ctx.options.smart_typography
+        || !text.contains(['"', '\'', '-', '.', ';', ':', '!', '?', '«', '»'])
+    {
+        return Cow::Borrowed(text);
+    }
+    let lang = ctx
+        .options
+        .lang
+        .as_deref()
+        .and_then(|lang| lang.split(['-', '_']).next())
+        .unwrap_or("en")
+        .to_ascii_lowercase();
+    let quotes = QUOTES
+        .get(lang.as_str())
+        .unwrap_or_else(|| QUOTES.get("en").unwrap());
+    let french = lang == "fr";
+
+    let chars = text.chars().collect::<Vec<_>>();
+    let mut output = String::with_capacity(text.len());
+    let mut idx = 0;
+    let mut single_quoted = false;
+    while idx < chars.len() {
+        let prev = idx.checked_sub(1).map(|idx| chars[idx]);
+        let next = chars.get(idx + 1).copied();
+        if let Some(length) = prev
+            .is_none_or(char::is_whitespace)
+            .then(|| url_length(&chars[idx..]))
+            .flatten()
+        {
+            output.extend(&chars[idx..idx + length]);
+            idx += length;
+            continue;
+        }
+
+        match chars[idx] {
+            '.' if chars[idx..].starts_with(&['.', '.', '.']) => {
+                output.push('…');
+                idx += 3;
+                continue;
+            }
+            '-' if chars[idx..].starts_with(&['-', '-', '-']) => {
+                output.push('—');
+                idx += 3;
+                continue;
+            }
+            '-' if next == Some('-') => {
+                output.push('–');
+                idx += 2;
+                continue;
+            }
+            '"' if is_opening(prev, next) => {
+                open_quote(&mut output, quotes.0, french);
+                // the space after an opening quote is replaced, not added to
+                if french && next.is_some_and(char::is_whitespace) {
+                    idx += 1;
+                }
+            }
+            '"' => close_quote(&mut output, quotes.1, french),
+            // an apostrophe looks the same in every language
+            '\'' if prev.is_some_and(char::is_alphanumeric)
+                && (!single_quoted || next.is_some_and(char::is_alphanumeric)) =>
+            {
+                output.push('’')
+            }
+            '\'' if is_opening(prev, next) => {
+                single_quoted = true;
+                output.push(quotes.2);
+            }
+            '\'' => {
+                single_quoted = false;
+                output.push(quotes.3);
+            }
+            '«' if french => {
+                open_quote(&mut output, '«', true);
+                if next.is_some_and(char::is_whitespace) {
+                    idx += 1;
+                }
+            }
+            '»' if french => close_quote(&mut output, '»', true),
+            c @ (';' | ':' | '!' | '?')
+                if french && next.is_none_or(|c| c.is_whitespace() || ";:!?».,)".contains(c)) =>
+            {
+                // French puts a space that can't be broken before these
+                let had_space = output.ends_with(' ');
+                if had_space {
+                    output.pop();
+                }
+                if had_space
+                    || output
+                        .chars()
+                        .last()
+                        .is_some_and(|c| c.is_alphanumeric() || "»”’)]".contains(c))
+                {
+                    output.push(if c == ':' {
+                        NO_BREAK_SPACE
+                    } else {
+                        NARROW_NO_BREAK_SPACE
+                    });
+                }
+                output.push(c);
+            }
+            c => output.push(c),
+        }
+        idx += 1;
+    }
+    Cow::Owned(output)
+}
+
+/// Quotes directly after whitespace are opening quotes, and quotes directly after text are
+/// closing quotes. At the start of text, i.e. after other markup, the text after it decides.
+fn is_opening(prev: Option<char>, next: Option<char>) -> bool {
+    let followed_by_text = next.is_some_and(|c| !c.is_whitespace() && !c.is_ascii_punctuation());
+    match prev {
+        None => followed_by_text,
+        Some(prev) => {
+            (prev.is_whitespace() || "([{—–-/".contains(prev))
+                && next.is_none_or(|c| !c.is_whitespace())
+        }
+    }
+}
+
+#[inline(always)]
+fn open_quote(output: &mut String, quote: char, french: bool) {
+    output.push(quote);
+    if french {
+        output.push(NO_BREAK_SPACE);
+    }
+}
+
+#[inline(always)]
+fn close_quote(output: &mut String, quote: char, french: bool) {
+    if french {
+        if output.ends_with(' ') {
+            output.pop();
+        }
+        output.push(NO_BREAK_SPACE);
+    }
+    output.push(quote);
+}
+
+/// Length of the URL at the start of `chars` in chars, without the punctuation after it
+fn url_length(chars: &[char]) -> Option<usize> {
+    let word = chars
+        .iter()
+        .take_while(|c| !c.is_whitespace())
+        .collect::<String>();
+    let length = word[..url_end(&word, 0)].chars().count();
+    (is_url(&word) && length > 0).then_some(length)
+}
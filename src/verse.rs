@@ -0,0 +1,50 @@
+use std::io::{BufReader, Read};
+
+use crate::{
+    directives::read_block_body,
+    escape,
+    extensions::HtmlWriting,
+    parser::{Context, parse_nested},
+};
+
+/// Renders a `<verse>` block, whose body is parsed as FXG with every line break preserved. Every
+/// stanza, which are separated by empty lines, is put in its own paragraph.
+pub fn parse_verse<T>(reader: &mut BufReader<T>, output: &mut String, ctx: &mut Context)
+where
+    T: Read,
+{
+    let body = read_block_body(reader);
+    let preserve_line_breaks = ctx.preserve_line_breaks;
+    ctx.preserve_line_breaks = true;
+    output.write_opening_tag("div", &[("class", "fxg-verse")]);
+    for stanza in split_stanzas(&body) {
+        let mut stanza = parse_nested(&stanza, ctx);
+        // the last line doesn't need to be followed by a line break
+        let line_break = format!("{}br{}", escape!("<"), escape!(">"));
+        if let Some(stripped) = stanza.strip_suffix(&line_break) {
+            stanza.truncate(stripped.len());
+        }
+        output.write_tag("p", &stanza, &[]);
+    }
+    output.write_closing_tag("div");
+    ctx.preserve_line_breaks = preserve_line_breaks;
+}
+
+fn split_stanzas(body: &str) -> Vec<String> {
+    let mut stanzas = vec![];
+    let mut stanza = String::new();
+    for line in body.lines() {
+        if line.trim().is_empty() {
+            if !stanza.is_empty() {
+                stanzas.push(std::mem::take(&mut stanza));
+            }
+        } else {
+            stanza.push_str(line);
+            stanza.push('\n');
+        }
+    }
+    if !stanza.is_empty() {
+        stanzas.push(stanza);
+    }
+    stanzas
+}
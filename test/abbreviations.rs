@@ -0,0 +1,32 @@
+use crate::parser;
+
+parser_tests! {
+    [abbreviation] {
+        "FXG renders to HTML.\n*[HTML]: HyperText Markup Language" ->
+        "FXG renders to <abbr title=\"HyperText Markup Language\">HTML</abbr>."
+    };
+    [abbreviation_whole_words] {
+        "*[HTML]: HyperText Markup Language\nXHTML HTMLS HTML_ HTML's" ->
+        "XHTML HTMLS HTML_ <abbr title=\"HyperText Markup Language\">HTML</abbr>'s"
+    };
+    [abbreviation_longest] {
+        "*[HTML]: HyperText Markup Language\n*[HTML5]: HTML, version 5\nHTML5 and HTML" ->
+        "<abbr title=\"HTML, version 5\">HTML5</abbr> and <abbr title=\"HyperText Markup Language\">HTML</abbr>"
+    };
+    [abbreviation_in_markup] {
+        "*[W3C]: World Wide Web Consortium\n!!W3C!! <>W3C</> https://example.com/W3C" ->
+        "<strong><abbr title=\"World Wide Web Consortium\">W3C</abbr></strong> <code>W3C</code> https://example.com/W3C"
+    };
+    [abbreviation_title_escaping] {
+        "*[FXG]: The \"better\" markup\nFXG" ->
+        "<abbr title=\"The &quot;better&quot; markup\">FXG</abbr>"
+    };
+    [abbreviation_in_codeblock] {
+        "<txt>\n*[FXG]: Not an abbreviation\n</>\nFXG" ->
+        "<pre><code class=\"language-txt\">*[FXG]: Not an abbreviation\n</code></pre>FXG"
+    };
+    [abbreviation_smart] (parser::Options { smart_typography: true, ..Default::default() }) {
+        "*[O'Reilly]: O'Reilly Media\nBooks by O'Reilly..." ->
+        "Books by <abbr title=\"O’Reilly Media\">O’Reilly</abbr>…"
+    };
+}
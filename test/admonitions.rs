@@ -0,0 +1,27 @@
+use crate::parser;
+
+parser_tests! {
+    [note] {
+        "<note>\nBe //careful//.\n</>" ->
+        "<aside class=\"fxg-note\">Be <em>careful</em>. </aside>"
+    };
+    [warning_title] {
+        "<warning title=\"Mind the !!gap!!\">\ntext\n</>" ->
+        "<aside class=\"fxg-warning\"><p class=\"fxg-admonition-title\">Mind the <strong>gap</strong></p>text </aside>"
+    };
+    [admonition_nested] {
+        "<tip>\n<rs>\n<note>\n</>\n<danger>\ninner\n</>\n</>" ->
+        "<aside class=\"fxg-tip\"><pre><code class=\"language-rs\">&lt;note&gt;\n</code></pre><aside class=\"fxg-danger\">inner </aside></aside>"
+    };
+    [admonition_sections] (parser::Options { sections: true, ..Default::default() }) {
+        "<note>\n== Inside ==\n</>" ->
+        "<aside class=\"fxg-note\"><section id=\"inside\"><h2>Inside</h2></section></aside>"
+    };
+    [admonition_unknown_option] {
+        "<note foo>\ntext\n</>" -> "<aside class=\"fxg-note\">text </aside>"
+    };
+    [admonition_directive_with_option] {
+        "<note>\n<verse x>\n</>\n</>\nafter\n\n== Later ==" ->
+        "<aside class=\"fxg-note\"><div class=\"fxg-verse\"></div></aside>after <br/><h2 id=\"later\">Later</h2>"
+    };
+}
@@ -0,0 +1,59 @@
+use crate::parser;
+
+parser_tests! {
+    [heading_attributes] {
+        "== Heading {#chosen .big data-level=2} ==" ->
+        "<h2 id=\"chosen\" class=\"big\" data-level=\"2\">Heading</h2>"
+    };
+    [heading_attributes_sections] (parser::Options { sections: true, ..Default::default() }) {
+        "== Heading {.big} ==" ->
+        "<section id=\"heading\"><h2 class=\"big\">Heading</h2></section>"
+    };
+    [heading_unknown_attribute] { "== Heading {title=x} ==" -> "<h2 id=\"heading-titlex\">Heading {title=x}</h2>" };
+    [table_attributes] {
+        "{#results .wide}\n| a |\n| 1 |" ->
+        "<table id=\"results\" class=\"wide\"><tr><td> a </td></tr><tr><td> 1 </td></tr></table>"
+    };
+    [attributes_without_table] { "{.wide}" -> "{.wide}" };
+    [codeblock_attributes] {
+        "<rs #example .small data-file=main.rs>\nfn main() {}\n</>" ->
+        "<pre id=\"example\" class=\"small\" data-file=\"main.rs\"><code class=\"language-rs\">fn main() {}\n</code></pre>"
+    };
+    [image_attributes] {
+        "<!cat.png A cat>{#cat .round width=10}" ->
+        "<img src=\"cat.png\" alt=\"A cat\" width=\"10\" loading=\"lazy\" id=\"cat\" class=\"round\">"
+    };
+    [link_classes] (parser::Options { external_class: Some("external".to_string()), ..Default::default() }) {
+        "<#https://example.com/ Example>{.button}" ->
+        "<a href=\"https://example.com/\" class=\"button external\">Example</a>"
+    };
+    [span] {
+        "some [//marked// text]{#marked .highlight} here" ->
+        "some <span id=\"marked\" class=\"highlight\"><em>marked</em> text</span> here"
+    };
+    [attribute_id_duplicate] {
+        "== Intro ==\n[x]{#intro}\n<rs #intro>\n</>\n{#intro}\n| a |\n| 1 |" ->
+        "<h2 id=\"intro\">Intro</h2><span id=\"intro-1\">x</span> <pre id=\"intro-2\"><code class=\"language-rs\"></code></pre><table id=\"intro-3\"><tr><td> a </td></tr><tr><td> 1 </td></tr></table>"
+    };
+    [span_without_attributes] { "[not] a span {.x}" -> "[not] a span {.x}" };
+    [attribute_escaping] {
+        "[text]{data-x=\"a'b\" data-y=<&>}" ->
+        "<span data-x=\"a'b\" data-y=\"&lt;&amp;&gt;\">text</span>"
+    };
+    [attribute_qoute_escaping] {
+        "<#a\"onclick=\"x Link>" ->
+        "<a href=\"a&quot;onclick=&quot;x\">Link</a>"
+    };
+}
+
+#[test]
+fn table_attributes_at_buffer_boundary() {
+    // the table starts right where the reader's buffer of 8 KiB ends
+    let input = "x".repeat(8178) + "\n\n{.wide}\n| a |\n| 1 |";
+    let mut reader = std::io::BufReader::new(input.as_bytes());
+    let result = parser::parse_with_options(&mut reader, &parser::Options::default());
+    assert!(
+        result
+            .ends_with("<table class=\"wide\"><tr><td> a </td></tr><tr><td> 1 </td></tr></table>")
+    );
+}
@@ -0,0 +1,34 @@
+use crate::parser;
+
+parser_tests! {
+    [autolink_url] (parser::Options { autolink: true, ..Default::default() }) {
+        "See https://example.com/docs for more." ->
+        "See <a href=\"https://example.com/docs\">https://example.com/docs</a> for more."
+    };
+    [autolink_trailing_punctuation] (parser::Options { autolink: true, ..Default::default() }) {
+        "Go to https://example.com/a." -> "Go to <a href=\"https://example.com/a\">https://example.com/a</a>."
+    };
+    [autolink_parentheses] (parser::Options { autolink: true, ..Default::default() }) {
+        "(see https://en.wikipedia.org/wiki/Rust_(language))" ->
+        "(see <a href=\"https://en.wikipedia.org/wiki/Rust_(language)\">https://en.wikipedia.org/wiki/Rust_(language)</a>)"
+    };
+    [autolink_www] (parser::Options { autolink: true, ..Default::default() }) {
+        "Visit www.example.com!" -> "Visit <a href=\"https://www.example.com\">www.example.com</a>!"
+    };
+    [autolink_email] (parser::Options { autolink: true, ..Default::default() }) {
+        "Mail mika@example.com." -> "Mail <a href=\"mailto:mika@example.com\">mika@example.com</a>."
+    };
+    [autolink_markup_in_url] (parser::Options { autolink: true, ..Default::default() }) {
+        "!!a!! http://a.com//b__c__ //d//" ->
+        "<strong>a</strong> <a href=\"http://a.com//b__c__\">http://a.com//b__c__</a> <em>d</em>"
+    };
+    [autolink_skips_links] (parser::Options { autolink: true, ..Default::default() }) {
+        "<#https://example.com/ see https://example.com/>" ->
+        "<a href=\"https://example.com/\">see https://example.com/</a>"
+    };
+    [autolink_skips_code] (parser::Options { autolink: true, ..Default::default() }) {
+        "<>https://example.com/</>" -> "<code>https://example.com/</code>"
+    };
+    [autolink_not_an_email] (parser::Options { autolink: true, ..Default::default() }) { "@ user@localhost" -> "@ user@localhost" };
+    [autolink_disabled] { "https://example.com/" -> "https://example.com/" };
+}
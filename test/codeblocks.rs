@@ -0,0 +1,49 @@
+use crate::parser;
+
+parser_tests! {
+    [codeblock] { "<rs>\nfn main() {}\n</>\n" -> "<pre><code class=\"language-rs\">fn main() {}\n</code></pre>" };
+    [codeblock_no_lang] { "<>\n    indented\n</>\n" -> "<pre><code>indented\n</code></pre>" };
+    [codeblock_multibyte_indent] {
+        "<rs>\n  x\n\u{3000}y\n</>" -> "<pre><code class=\"language-rs\"> x\ny\n</code></pre>"
+    };
+
+    [codeblock_title] {
+        "<rs title=src/main.rs>\nfn main() {}\n</>\n" ->
+        "<figure class=\"fxg-codeblock\"><figcaption>src/main.rs</figcaption><pre><code class=\"language-rs\">fn main() {}\n</code></pre></figure>"
+    };
+    [codeblock_qouted_title] {
+        "<rs title=\"main file\">\nfn main() {}\n</>\n" ->
+        "<figure class=\"fxg-codeblock\"><figcaption>main file</figcaption><pre><code class=\"language-rs\">fn main() {}\n</code></pre></figure>"
+    };
+    [codeblock_numbers] {
+        "<rs numbers>\na\nb\n</>\n" ->
+        "<pre><code class=\"language-rs\"><span class=\"fxg-line\" id=\"fxg-code1-L1\"><a class=\"fxg-lineno\" href=\"#fxg-code1-L1\">1</a>a</span>\n<span class=\"fxg-line\" id=\"fxg-code1-L2\"><a class=\"fxg-lineno\" href=\"#fxg-code1-L2\">2</a>b</span>\n</code></pre>"
+    };
+    [codeblock_highlighted_lines] {
+        "<rs lines=2-3>\na\nb\nc\n</>\n" ->
+        "<pre><code class=\"language-rs\"><span class=\"fxg-line\">a</span>\n<mark class=\"fxg-line\">b</mark>\n<mark class=\"fxg-line\">c</mark>\n</code></pre>"
+    };
+    [codeblock_highlighted_line_list] {
+        "<rs lines=1,3>\na\nb\nc\n</>\n" ->
+        "<pre><code class=\"language-rs\"><mark class=\"fxg-line\">a</mark>\n<span class=\"fxg-line\">b</span>\n<mark class=\"fxg-line\">c</mark>\n</code></pre>"
+    };
+    [codeblock_unknown_option] { "<rs colour=red>" -> "&lt;rs colour=red&gt;" };
+    [include] (parser::Options { base_dir: "test/fixtures".into(), ..Default::default() }) {
+        "<rs include=include.rs>" ->
+        "<pre><code class=\"language-rs\">use std::io;\n\n// fxg-region: main\nfn main() {\n    println!(\"Hello, world!\");\n}\n// fxg-endregion: main\n</code></pre>"
+    };
+    [include_range] (parser::Options { base_dir: "test/fixtures".into(), ..Default::default() }) {
+        "<rs include=include.rs range=4-6>" ->
+        "<pre><code class=\"language-rs\">fn main() {\n    println!(\"Hello, world!\");\n}\n</code></pre>"
+    };
+    [include_multiple_ranges] (parser::Options { base_dir: "test/fixtures".into(), ..Default::default() }) {
+        "<rs include=include.rs range=1,5-6,20>" ->
+        "<pre><code class=\"language-rs\">use std::io;\n    println!(\"Hello, world!\");\n}\n</code></pre>"
+    };
+    [include_region_numbers] (parser::Options { base_dir: "test/fixtures".into(), ..Default::default() }) {
+        "<rs include=include.rs region=main numbers lines=5>" ->
+        "<pre><code class=\"language-rs\"><span class=\"fxg-line\" id=\"fxg-code1-L4\"><a class=\"fxg-lineno\" href=\"#fxg-code1-L4\">4</a>fn main() {</span>\n<mark class=\"fxg-line\" id=\"fxg-code1-L5\"><a class=\"fxg-lineno\" href=\"#fxg-code1-L5\">5</a>    println!(\"Hello, world!\");</mark>\n<span class=\"fxg-line\" id=\"fxg-code1-L6\"><a class=\"fxg-lineno\" href=\"#fxg-code1-L6\">6</a>}</span>\n</code></pre>"
+    };
+    [include_missing_file] (parser::Options { base_dir: "test/fixtures".into(), ..Default::default() }) { "<rs include=missing.rs>\ntext" -> "text" };
+    [include_missing_region] (parser::Options { base_dir: "test/fixtures".into(), ..Default::default() }) { "<rs include=include.rs region=missing>\ntext" -> "text" };
+}
@@ -0,0 +1,12 @@
+use crate::parser;
+
+parser_tests! {
+    [line_comment] { "%%% TODO: rewrite this\ntext" -> "text" };
+    [line_comment_highlight] { "%%highlight%%" -> "<mark>highlight</mark>" };
+    [line_comment_percentage] { "% of users grew" -> "% of users grew" };
+    [block_comment] { "<comment>\nnot //rendered//\n<rs>\n</>\ntext" -> "text" };
+    [block_comment_after_title] { "= A =\n<comment>\nx\n</>\n\ntext" -> "<h1 id=\"a\">A</h1><br/>text" };
+    [comment_in_admonition] { "<note>\n<comment>\nhidden\n</>\nshown\n</>" -> "<aside class=\"fxg-note\">shown </aside>" };
+    [kept_line_comment] (parser::Options { keep_comments: true, ..Default::default() }) { "%%% check <this> -->\ntext" -> "<!-- check &lt;this&gt; - -&gt; -->text" };
+    [kept_block_comment] (parser::Options { keep_comments: true, ..Default::default() }) { "<comment>\nfirst\nsecond\n</>" -> "<!-- first\nsecond -->" };
+}
@@ -0,0 +1,29 @@
+use crate::parser;
+
+parser_tests! {
+    [definition_list] {
+        "; //FXG//\n: A markup language." ->
+        "<dl><dt><em>FXG</em></dt><dd>A markup language.</dd></dl>"
+    };
+    [definition_list_multiple] {
+        "; fxg\n; FXG\n: one\n: two" ->
+        "<dl><dt>fxg</dt><dt>FXG</dt><dd>one</dd><dd>two</dd></dl>"
+    };
+    [definition_paragraphs] {
+        "; FXG\n: A markup language.\n:: It is !!not!! Markdown." ->
+        "<dl><dt>FXG</dt><dd><p>A markup language.</p><p>It is <strong>not</strong> Markdown.</p></dd></dl>"
+    };
+    [definition_list_end] {
+        "; term\n: definition\ntext" ->
+        "<dl><dt>term</dt><dd>definition</dd></dl>text"
+    };
+}
+
+#[test]
+fn definition_list_at_buffer_boundary() {
+    // the list starts right where the reader's buffer of 8 KiB ends
+    let input = "x".repeat(8171) + "\n\n; T\n: def one\n; U\n: def two\n";
+    let mut reader = std::io::BufReader::new(input.as_bytes());
+    let result = parser::parse_with_options(&mut reader, &parser::Options::default());
+    assert!(result.ends_with("<dl><dt>T</dt><dd>def one</dd><dt>U</dt><dd>def two</dd></dl>"));
+}
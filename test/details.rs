@@ -0,0 +1,19 @@
+use crate::parser;
+
+parser_tests! {
+    [details] {
+        "<details title=\"Why //FXG//?\">\nBecause.\n</>" ->
+        "<details><summary>Why <em>FXG</em>?</summary>Because. </details>"
+    };
+    [details_open] {
+        "<details open>\n- item\n</>" ->
+        "<details open=\"\"><ul><li>item</li></ul></details>"
+    };
+    [details_nested] {
+        "<details title=Outer>\n<details title=Inner>\ntext\n</>\n</>" ->
+        "<details><summary>Outer</summary><details><summary>Inner</summary>text </details></details>"
+    };
+    [details_unknown_option] {
+        "<details closed>\ntext\n</>" -> "<details>text </details>"
+    };
+}
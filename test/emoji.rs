@@ -0,0 +1,12 @@
+use crate::parser;
+
+parser_tests! {
+    [emoji_shortcodes] (parser::Options { emoji: true, ..Default::default() }) { "Shipped :tada: :rocket::+1:" -> "Shipped 🎉 🚀👍" };
+    [emoji_in_markup] (parser::Options { emoji: true, ..Default::default() }) { "!!:fire:!!" -> "<strong>🔥</strong>" };
+    [emoji_unknown] (parser::Options { emoji: true, ..Default::default() }) { "at 10:30 :not_an_emoji: :tada" -> "at 10:30 :not_an_emoji: :tada" };
+    [emoji_code_and_urls] (parser::Options { emoji: true, ..Default::default() }) {
+        "<>:tada:</> https://example.com/:tada: <#https://example.com/:tada:>" ->
+        "<code>:tada:</code> https://example.com/:tada: <a href=\"https://example.com/:tada:\">https://example.com/:tada:</a>"
+    };
+    [emoji_disabled] { ":tada:" -> ":tada:" };
+}
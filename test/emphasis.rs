@@ -6,16 +6,31 @@ parser_tests! {
     [cursive] { "//cursive//" -> "<em>cursive</em>" };
     [bold] { "!!bold!!" -> "<strong>bold</strong>" };
     [underline] { "__underline__" -> "<u>underline</u>" };
+    [small] { "??small??" -> "<small>small</small>" };
+    [strikethrough] { "~~strikethrough~~" -> "<del>strikethrough</del>" };
+    [highlight] { "%%highlight%%" -> "<mark>highlight</mark>" };
+    [superscript] { "2^^10^^" -> "2<sup>10</sup>" };
+    [subscript] { "H,,2,,O" -> "H<sub>2</sub>O" };
+    [keyboard] { "@@Ctrl@@+@@C@@" -> "<kbd>Ctrl</kbd>+<kbd>C</kbd>" };
+    [cursive_after_url] { "https://example.com/ //cursive//" -> "https://example.com/ <em>cursive</em>" };
+    [nested_markup] { "~~//both//~~" -> "<del><em>both</em></del>" };
 
     [cursive_with_escape] { "//cursive \\// still cursive//" -> "<em>cursive // still cursive</em>" };
     [bold_with_escape] { "!!bold \\!! still bold!!" -> "<strong>bold !! still bold</strong>" };
     [underline_with_escape] { "__underline \\__ still underline__" -> "<u>underline __ still underline</u>" };
+    [small_with_escape] { "??small \\?? still small??" -> "<small>small ?? still small</small>" };
+    [strikethrough_with_escape] { "~~strikethrough \\~~ still strikethrough~~" -> "<del>strikethrough ~~ still strikethrough</del>" };
 
     [cursive_no_format] { "//cursive" -> "//cursive" };
     [bold_no_format] { "!!bold" -> "!!bold" };
     [underline_no_format] { "__underline" -> "__underline" };
+    [small_no_format] { "??small" -> "??small" };
+    [highlight_no_format] { "%%highlight" -> "%%highlight" };
+    [keyboard_no_format] { "@@Ctrl" -> "@@Ctrl" };
 
     [cursive_with_escaped_final_tag] { "//cursive \\//" -> "//cursive //" };
     [bold_with_escaped_final_tag] { "!!bold \\!!" -> "!!bold !!" };
+    [strikethrough_multibyte] { "~~é~~ and //ü \\// ö//" -> "<del>é</del> and <em>ü // ö</em>" };
+    [empty_markup] { "a ,,,, b ////" -> "a ,,,, b ////" };
     [underline_with_escaped_final_tag] { "__underline \\__" -> "__underline __" };
 }
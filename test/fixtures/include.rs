@@ -0,0 +1,7 @@
+use std::io;
+
+// fxg-region: main
+fn main() {
+    println!("Hello, world!");
+}
+// fxg-endregion: main
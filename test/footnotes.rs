@@ -0,0 +1,23 @@
+use crate::parser;
+
+parser_tests! {
+    [footnote] {
+        "Text<^a>.\n<^a>: A //footnote//." ->
+        "Text<sup class=\"fxg-fnref\"><a id=\"fxg-fnref-a\" href=\"#fxg-fn-a\">1</a></sup>. <section class=\"fxg-footnotes\"><ol><li id=\"fxg-fn-a\">A <em>footnote</em>. <a class=\"fxg-backref\" href=\"#fxg-fnref-a\">↩</a></li></ol></section>"
+    };
+    [footnotes_numbered_by_reference] {
+        "<^b>: B\n<^a>: A\nOne<^a> two<^b>" ->
+        "One<sup class=\"fxg-fnref\"><a id=\"fxg-fnref-a\" href=\"#fxg-fn-a\">1</a></sup> two<sup class=\"fxg-fnref\"><a id=\"fxg-fnref-b\" href=\"#fxg-fn-b\">2</a></sup> <section class=\"fxg-footnotes\"><ol><li id=\"fxg-fn-a\">A <a class=\"fxg-backref\" href=\"#fxg-fnref-a\">↩</a></li><li id=\"fxg-fn-b\">B <a class=\"fxg-backref\" href=\"#fxg-fnref-b\">↩</a></li></ol></section>"
+    };
+    [footnote_referenced_twice] {
+        "A<^a> B<^a>\n<^a>: Note" ->
+        "A<sup class=\"fxg-fnref\"><a id=\"fxg-fnref-a\" href=\"#fxg-fn-a\">1</a></sup> B<sup class=\"fxg-fnref\"><a id=\"fxg-fnref-a-2\" href=\"#fxg-fn-a\">1</a></sup> <section class=\"fxg-footnotes\"><ol><li id=\"fxg-fn-a\">Note <a class=\"fxg-backref\" href=\"#fxg-fnref-a\">↩</a> <a class=\"fxg-backref\" href=\"#fxg-fnref-a-2\">↩</a></li></ol></section>"
+    };
+    [footnote_in_heading] {
+        "<toc>\n== A<^n> ==\nSee <##a>.\n<^n>: Note" ->
+        "<nav class=\"fxg-toc\"><ul><li><a href=\"#a\">A</a></li></ul></nav><h2 id=\"a\">A<sup class=\"fxg-fnref\"><a id=\"fxg-fnref-n\" href=\"#fxg-fn-n\">1</a></sup></h2>See <a href=\"#a\">A</a>. <section class=\"fxg-footnotes\"><ol><li id=\"fxg-fn-n\">Note <a class=\"fxg-backref\" href=\"#fxg-fnref-n\">↩</a></li></ol></section>"
+    };
+    [footnote_undefined] { "Text<^missing>" -> "Text&lt;^missing&gt;" };
+    [footnote_unused] { "<^a>: Never used" -> "" };
+    [footnote_unclosed] { "Text<^ not a footnote" -> "Text&lt;^ not a footnote" };
+}
@@ -1,12 +1,12 @@
 use crate::parser;
 
 parser_tests! {
-    [header_1] { "= Heading 1 ="           -> "<h1>Heading 1</h1>" };
-    [header_2] { "== Heading 2 =="         -> "<h2>Heading 2</h2>" };
-    [header_3] { "=== Heading 3 ==="       -> "<h3>Heading 3</h3>" };
-    [header_4] { "==== Heading 4 ===="     -> "<h4>Heading 4</h4>" };
-    [header_5] { "===== Heading 5 ====="   -> "<h5>Heading 5</h5>" };
-    [header_6] { "====== Heading 6 ======" -> "<h6>Heading 6</h6>" };
+    [header_1] { "= Heading 1 ="           -> "<h1 id=\"heading-1\">Heading 1</h1>" };
+    [header_2] { "== Heading 2 =="         -> "<h2 id=\"heading-2\">Heading 2</h2>" };
+    [header_3] { "=== Heading 3 ==="       -> "<h3 id=\"heading-3\">Heading 3</h3>" };
+    [header_4] { "==== Heading 4 ===="     -> "<h4 id=\"heading-4\">Heading 4</h4>" };
+    [header_5] { "===== Heading 5 ====="   -> "<h5 id=\"heading-5\">Heading 5</h5>" };
+    [header_6] { "====== Heading 6 ======" -> "<h6 id=\"heading-6\">Heading 6</h6>" };
 
     [excess_equals_signs] { "======== Heading 8 ========" ->  "======== Heading 8 ========" };
     [fully_unclosed_header] { "== Heading 2" -> "== Heading 2" };
@@ -15,4 +15,66 @@ parser_tests! {
     [empty_header] { "= =" -> "<h1></h1>" };
     [empty_header_3] { "=== ===" -> "<h3></h3>" };
 
+    [header_slug_markup] { "== A //Styled// Heading! ==" -> "<h2 id=\"a-styled-heading\">A <em>Styled</em> Heading!</h2>" };
+    [header_slug_unicode] { "== Überschrift  — Ω ==" -> "<h2 id=\"überschrift-ω\">Überschrift  — Ω</h2>" };
+    [header_slug_duplicate] {
+        "== Usage ==\n== Usage ==\n== Usage ==" ->
+        "<h2 id=\"usage\">Usage</h2><h2 id=\"usage-1\">Usage</h2><h2 id=\"usage-2\">Usage</h2>"
+    };
+    [header_explicit_id] { "== Getting started {#start} ==" -> "<h2 id=\"start\">Getting started</h2>" };
+    [header_explicit_id_duplicate] {
+        "== Start ==\n== Other {#start} ==" ->
+        "<h2 id=\"start\">Start</h2><h2 id=\"start-1\">Other</h2>"
+    };
+    [header_permalink] (parser::Options { permalinks: true, ..Default::default() }) {
+        "== Heading 2 ==" -> "<h2 id=\"heading-2\">Heading 2<a class=\"fxg-permalink\" href=\"#heading-2\">#</a></h2>"
+    };
+
+}
+
+parser_tests! {
+    [numbered_headings] (parser::Options { number_headings: true, ..Default::default() }) {
+        "= Title =\n== A ==\n=== B ===\n=== C ===\n== D ==\n=== E ===" ->
+        "<h1 id=\"title\">Title</h1><h2 id=\"a\"><span class=\"fxg-secnum\">1</span> A</h2><h3 id=\"b\"><span class=\"fxg-secnum\">1.1</span> B</h3><h3 id=\"c\"><span class=\"fxg-secnum\">1.2</span> C</h3><h2 id=\"d\"><span class=\"fxg-secnum\">2</span> D</h2><h3 id=\"e\"><span class=\"fxg-secnum\">2.1</span> E</h3>"
+    };
+    [numbered_headings_skipped_level] (parser::Options { number_headings: true, ..Default::default() }) {
+        "=== A ===" -> "<h3 id=\"a\"><span class=\"fxg-secnum\">0.1</span> A</h3>"
+    };
+    [unnumbered_heading] (parser::Options { number_headings: true, ..Default::default() }) {
+        "== Preface {-} ==\n== A ==" ->
+        "<h2 id=\"preface\">Preface</h2><h2 id=\"a\"><span class=\"fxg-secnum\">1</span> A</h2>"
+    };
+    [unnumbered_heading_with_id] (parser::Options { number_headings: true, ..Default::default() }) {
+        "== Preface {#pre -} ==" -> "<h2 id=\"pre\">Preface</h2>"
+    };
+    [numbered_toc] (parser::Options { number_headings: true, ..Default::default() }) {
+        "<toc>\n== A ==" ->
+        "<nav class=\"fxg-toc\"><ul><li><a href=\"#a\"><span class=\"fxg-secnum\">1</span> A</a></li></ul></nav><h2 id=\"a\"><span class=\"fxg-secnum\">1</span> A</h2>"
+    };
+    [cross_reference_numbered] (parser::Options { number_headings: true, ..Default::default() }) {
+        "See <##b>.\n== A ==\n=== B ===" ->
+        "See <a href=\"#b\">1.1</a>. <h2 id=\"a\"><span class=\"fxg-secnum\">1</span> A</h2><h3 id=\"b\"><span class=\"fxg-secnum\">1.1</span> B</h3>"
+    };
+    [cross_reference_unnumbered] {
+        "See <##a>.\n== //A// ==" -> "See <a href=\"#a\"><em>A</em></a>. <h2 id=\"a\"><em>A</em></h2>"
+    };
+    [cross_reference_missing] { "See <##missing>." -> "See <a href=\"#missing\">#missing</a>." };
+    [header_empty_braces] { "== Set {} ==" -> "<h2 id=\"set\">Set {}</h2>" };
+}
+
+parser_tests! {
+    [sections] (parser::Options { sections: true, ..Default::default() }) {
+        "== A ==\ntext\n=== B ===\n== C ==" ->
+        "<section id=\"a\"><h2>A</h2>text <section id=\"b\"><h3>B</h3></section></section><section id=\"c\"><h2>C</h2></section>"
+    };
+    [sections_mismatched_levels] (parser::Options { sections: true, ..Default::default() }) {
+        "== A ==\n==== B ====\n=== C ===\n= D =" ->
+        "<section id=\"a\"><h2>A</h2><section id=\"b\"><h4>B</h4></section><section id=\"c\"><h3>C</h3></section></section><section id=\"d\"><h1>D</h1></section>"
+    };
+    [sections_content_before_heading] (parser::Options { sections: true, ..Default::default() }) {
+        "intro\n== A ==" -> "intro <section id=\"a\"><h2>A</h2></section>"
+    };
+    [sections_permalink] (parser::Options { sections: true, permalinks: true, ..Default::default() }) {
+        "== A ==" -> "<section id=\"a\"><h2>A<a class=\"fxg-permalink\" href=\"#a\">#</a></h2></section>"
+    };
 }
@@ -0,0 +1,30 @@
+use crate::parser;
+
+parser_tests! {
+    [raw_html] {
+        "<rawhtml>\n<iframe src=\"https://example.com/?a=1&amp;b=2\"></iframe>\n</>" ->
+        "<iframe src=\"https://example.com/?a=1&amp;b=2\"></iframe>"
+    };
+    [raw_html_no_markup] { "<rawhtml>\n<b>//not cursive//</b>\n</>" -> "<b>//not cursive//</b>" };
+    [html_codeblock] {
+        "<html>\n<b>x</b>\n</>" ->
+        "<pre><code class=\"language-html\">&lt;b&gt;x&lt;/b&gt;\n</code></pre>"
+    };
+    [raw_html_unclosed] { "<rawhtml>\n<hr>" -> "<hr>" };
+    [sanitized_html] (parser::Options { sanitize_html: true, ..Default::default() }) {
+        "<rawhtml>\n<p class=intro onclick=\"steal()\">Hi &amp; <b>bye</b> a < b<script>alert(1)</script></p>\n</>" ->
+        "<p class=\"intro\">Hi &amp; <b>bye</b> a &lt; b</p>"
+    };
+    [sanitized_html_unknown_tags] (parser::Options { sanitize_html: true, ..Default::default() }) {
+        "<rawhtml>\n<svg><text>kept</text></svg><!-- removed -->\n</>" ->
+        "kept"
+    };
+    [sanitized_html_urls] (parser::Options { sanitize_html: true, ..Default::default() }) {
+        "<rawhtml>\n<a href=\"javascript:alert(1)\">a</a><a href=\" JavaScript&#58;alert(1)\">b</a><a href=\"/page?a=1&amp;b=2\">c</a><img src=\"cat.png\" alt='a \"cat\"'>\n</>" ->
+        "<a>a</a><a>b</a><a href=\"/page?a=1&amp;b=2\">c</a><img src=\"cat.png\" alt=\"a &quot;cat&quot;\">"
+    };
+    [sanitized_html_balanced] (parser::Options { sanitize_html: true, ..Default::default() }) {
+        "<rawhtml>\n</div><i><b>text</i>\n</>" ->
+        "<i><b>text</b></i>"
+    };
+}
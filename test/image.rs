@@ -2,12 +2,48 @@ use crate::parser;
 
 parser_tests! {
     [link] { "<#https://example.com/ Example>" -> "<a href=\"https://example.com/\">Example</a>" };
-    [image] { "<!https://example.com/img.jpg Example>" -> "<img src=\"https://example.com/img.jpg\" alt=\"Example\">" };
+    [image] { "<!https://example.com/img.jpg Example>" -> "<img src=\"https://example.com/img.jpg\" alt=\"Example\" loading=\"lazy\">" };
 
     [link_unclosed] { "<#not a link" -> "&lt;#not a link" };
     [image_unclosed] { "<!not an image" -> "&lt;!not an image" };
 
     [link_no_contents] { "<#https://google.com/>" -> "<a href=\"https://google.com/\">https://google.com/</a>" };
 
-    [image_no_alt] { "<!https://example.com/>" -> "<img src=\"https://example.com/\">" };
+    [image_no_alt] { "<!https://example.com/>" -> "<img src=\"https://example.com/\" loading=\"lazy\">" };
+
+    [reference_link] {
+        "<#[home] My website>\n[home]: https://example.com/" -> "<a href=\"https://example.com/\">My website</a>"
+    };
+    [reference_link_no_contents] {
+        "[home]: https://example.com/\n<#[home]>" -> "<a href=\"https://example.com/\">https://example.com/</a>"
+    };
+    [reference_image] {
+        "<![cat] A cat>\n[cat]: https://example.com/cat.jpg" -> "<img src=\"https://example.com/cat.jpg\" alt=\"A cat\" loading=\"lazy\">"
+    };
+    [reference_link_undefined] { "<#[missing] text>" -> "&lt;#[missing] text&gt;" };
+    [reference_link_in_codeblock] {
+        "<fxg>\n[home]: https://example.com/\n</>\n<#[home]>" ->
+        "<pre><code class=\"language-fxg\">[home]: https://example.com/\n</code></pre>&lt;#[home]&gt;"
+    };
+    [image_size] {
+        "<!https://example.com/a.jpg A>{width=200 height=100}" ->
+        "<img src=\"https://example.com/a.jpg\" alt=\"A\" width=\"200\" height=\"100\" loading=\"lazy\">"
+    };
+    [image_eager] {
+        "<!https://example.com/a.jpg>{loading=eager} text" ->
+        "<img src=\"https://example.com/a.jpg\" loading=\"eager\"> text"
+    };
+    [image_figure] {
+        "<!https://example.com/a.jpg A cat>{caption=\"My //cat//\"}" ->
+        "<figure class=\"fxg-figure\"><img src=\"https://example.com/a.jpg\" alt=\"A cat\" loading=\"lazy\"><figcaption>My <em>cat</em></figcaption></figure>"
+    };
+    [image_caption_inline] {
+        "See <!a.jpg>{caption=cat}" -> "See <img src=\"a.jpg\" loading=\"lazy\">"
+    };
+    [image_size_png] (parser::Options { base_dir: "test/fixtures".into(), ..Default::default() }) { "<!image.png>" -> "<img src=\"image.png\" width=\"64\" height=\"32\" loading=\"lazy\">" };
+    [image_size_gif] (parser::Options { base_dir: "test/fixtures".into(), ..Default::default() }) { "<!image.gif>" -> "<img src=\"image.gif\" width=\"17\" height=\"9\" loading=\"lazy\">" };
+    [image_size_jpeg] (parser::Options { base_dir: "test/fixtures".into(), ..Default::default() }) { "<!image.jpg>" -> "<img src=\"image.jpg\" width=\"120\" height=\"48\" loading=\"lazy\">" };
+    [image_size_webp] (parser::Options { base_dir: "test/fixtures".into(), ..Default::default() }) { "<!image.webp>" -> "<img src=\"image.webp\" width=\"300\" height=\"200\" loading=\"lazy\">" };
+    [image_size_given] (parser::Options { base_dir: "test/fixtures".into(), ..Default::default() }) { "<!image.png>{width=10}" -> "<img src=\"image.png\" width=\"10\" loading=\"lazy\">" };
+    [image_size_missing_file] (parser::Options { base_dir: "test/fixtures".into(), ..Default::default() }) { "<!missing.png>" -> "<img src=\"missing.png\" loading=\"lazy\">" };
 }
@@ -0,0 +1,16 @@
+use crate::parser;
+
+parser_tests! {
+    [hard_break] { "Some Street 1\\\n1234 AB City" -> "Some Street 1<br>1234 AB City" };
+    [hard_break_with_space] { "first \\\nsecond" -> "first<br>second" };
+    [hard_break_in_markup] { "!!first!!\\\nsecond" -> "<strong>first</strong><br>second" };
+    [escaped_hard_break] { "not a break \\\\\nnext" -> "not a break \\ next" };
+    [verse] {
+        "<verse>\nRoses are //red//,\nviolets are blue.\n\nSecond stanza\n</>" ->
+        "<div class=\"fxg-verse\"><p>Roses are <em>red</em>,<br>violets are blue.</p><p>Second stanza</p></div>"
+    };
+    [verse_ends] {
+        "<verse>\nline\n</>\nfirst\nsecond" ->
+        "<div class=\"fxg-verse\"><p>line</p></div>first second"
+    };
+}
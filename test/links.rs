@@ -0,0 +1,32 @@
+use crate::parser;
+
+parser_tests! {
+    [link_title] {
+        "<#https://example.com/ Example>{title=\"An example\"}" ->
+        "<a href=\"https://example.com/\" title=\"An example\">Example</a>"
+    };
+    [link_new_tab] {
+        "<#https://example.com/ Example>{target=_blank} text" ->
+        "<a href=\"https://example.com/\" target=\"_blank\" rel=\"noopener noreferrer\">Example</a> text"
+    };
+    [link_rel_merged] {
+        "<#https://example.com/>{rel=noopener target=_blank}" ->
+        "<a href=\"https://example.com/\" target=\"_blank\" rel=\"noopener noreferrer\">https://example.com/</a>"
+    };
+    [link_unknown_attribute] {
+        "<#https://example.com/ Example>{colour=red}" ->
+        "<a href=\"https://example.com/\">Example</a>{colour=red}"
+    };
+    [link_off_site] (parser::Options { site_host: Some("example.com".to_string()), nofollow: true, external_class: Some("external".to_string()), ..Default::default() }) {
+        "<#https://other.org/ Other>" ->
+        "<a href=\"https://other.org/\" rel=\"nofollow\" class=\"external\">Other</a>"
+    };
+    [link_on_site] (parser::Options { site_host: Some("example.com".to_string()), nofollow: true, external_class: Some("external".to_string()), ..Default::default() }) {
+        "<#https://www.example.com/about About> <#/about About>" ->
+        "<a href=\"https://www.example.com/about\">About</a> <a href=\"/about\">About</a>"
+    };
+    [link_off_site_no_site_host] (parser::Options { nofollow: true, ..Default::default() }) {
+        "<#//cdn.example.com/ CDN>{target=_blank}" ->
+        "<a href=\"//cdn.example.com/\" target=\"_blank\" rel=\"noopener noreferrer nofollow\">CDN</a>"
+    };
+}
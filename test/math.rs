@@ -0,0 +1,42 @@
+use crate::parser;
+
+parser_tests! {
+    [math_inline] {
+        "Area: $$\\pi r^2$$." ->
+        "Area: <math display=\"inline\"><mi>π</mi><msup><mi>r</mi><mn>2</mn></msup></math>."
+    };
+    [math_operators] {
+        "$$a < b$$" -> "<math display=\"inline\"><mi>a</mi><mo>&lt;</mo><mi>b</mi></math>"
+    };
+    [math_multibyte_whitespace] {
+        "$$a\u{A0}b$$" -> "<math display=\"inline\"><mi>a</mi><mi>b</mi></math>"
+    };
+    [math_frac_sqrt] {
+        "$$\\frac{1}{\\sqrt{x}}$$" ->
+        "<math display=\"inline\"><mfrac><mrow><mn>1</mn></mrow><mrow><msqrt><mrow><mi>x</mi></mrow></msqrt></mrow></mfrac></math>"
+    };
+    [math_root] {
+        "$$\\sqrt[3]{8}$$" ->
+        "<math display=\"inline\"><mroot><mrow><mn>8</mn></mrow><mrow><mn>3</mn></mrow></mroot></math>"
+    };
+    [math_scripts] {
+        "$$x_{i}^2 + 10.5$$" ->
+        "<math display=\"inline\"><msubsup><mi>x</mi><mrow><mi>i</mi></mrow><mn>2</mn></msubsup><mo>+</mo><mn>10.5</mn></math>"
+    };
+    [math_text] {
+        "$$v = 3 \\text{ m/s}$$" ->
+        "<math display=\"inline\"><mi>v</mi><mo>=</mo><mn>3</mn><mtext> m/s</mtext></math>"
+    };
+    [math_no_markup] {
+        "$$a//b//c$$" ->
+        "<math display=\"inline\"><mi>a</mi><mo>/</mo><mo>/</mo><mi>b</mi><mo>/</mo><mo>/</mo><mi>c</mi></math>"
+    };
+    [math_block] {
+        "<math>\n\\sum_{k=1}^n k\n</>" ->
+        "<math display=\"block\"><msubsup><mo>∑</mo><mrow><mi>k</mi><mo>=</mo><mn>1</mn></mrow><mi>n</mi></msubsup><mi>k</mi></math>"
+    };
+    [math_unknown_command] { "$$\\foo x$$" -> "<code class=\"fxg-math-error\">\\foo x</code>" };
+    [math_unclosed_group] { "$${x$$" -> "<code class=\"fxg-math-error\">{x</code>" };
+    [math_double_script] { "$$x^^2$$" -> "<code class=\"fxg-math-error\">x^^2</code>" };
+    [math_unclosed] { "costs $$5" -> "costs $$5" };
+}
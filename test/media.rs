@@ -0,0 +1,18 @@
+use crate::parser;
+
+parser_tests! {
+    [video] {
+        "<@talk.mp4>" ->
+        "<video src=\"talk.mp4\" controls=\"\"><a href=\"talk.mp4\">talk.mp4</a></video>"
+    };
+    [video_poster_caption] {
+        "<@talk.webm My //talk//>{poster=talk.jpg width=640}" ->
+        "<figure class=\"fxg-media\"><video src=\"talk.webm\" controls=\"\" poster=\"talk.jpg\" width=\"640\"><a href=\"talk.webm\">talk.webm</a></video><figcaption>My <em>talk</em></figcaption></figure>"
+    };
+    [audio] {
+        "<@https://example.com/episode-1.MP3?dl=1 Episode 1>{poster=cover.jpg}" ->
+        "<figure class=\"fxg-media\"><audio src=\"https://example.com/episode-1.MP3?dl=1\" controls=\"\"><a href=\"https://example.com/episode-1.MP3?dl=1\">https://example.com/episode-1.MP3?dl=1</a></audio><figcaption>Episode 1</figcaption></figure>"
+    };
+    [media_unknown_type] { "<@slides.pdf The slides>" -> "<a href=\"slides.pdf\">The slides</a>" };
+    [media_inline] { "see <@talk.mp4>" -> "see &lt;@talk.mp4&gt;" };
+}
@@ -1,21 +1,42 @@
 macro_rules! parser_tests {
-    ($([$test_name:ident] { $input:literal -> $output:literal });*;) => {
+    (@options) => { parser::Options::default() };
+    (@options $options:expr) => { $options };
+    ($([$test_name:ident] $(($options:expr))? { $input:literal -> $output:literal });*;) => {
         $(
             #[test]
             fn $test_name() {
                 const INPUT: &'static str = $input;
                 const OUTPUT: &'static str = $output;
+                let options = parser_tests!(@options $($options)?);
                 let mut reader = std::io::BufReader::new(INPUT.as_bytes());
-                let result = parser::parse(&mut reader);
+                let result = parser::parse_with_options(&mut reader, &options);
                 assert_eq!(result.trim(), OUTPUT);
             }
         )*
     };
 }
 
+pub mod abbreviations;
+pub mod admonitions;
+pub mod attributes;
+pub mod autolinks;
 pub mod code;
+pub mod codeblocks;
+pub mod comments;
+pub mod definitions;
+pub mod details;
+pub mod emoji;
 pub mod emphasis;
+pub mod footnotes;
 pub mod headings;
 pub mod horizontal_rules;
+pub mod html;
 pub mod html_entities;
 pub mod image;
+pub mod line_breaks;
+pub mod links;
+pub mod math;
+pub mod media;
+pub mod safe;
+pub mod toc;
+pub mod typography;
@@ -0,0 +1,36 @@
+use crate::parser;
+
+parser_tests! {
+    [safe_link] (parser::Options { safe: true, ..Default::default() }) {
+        "<#https://example.com/ Example> <#/about About>" ->
+        "<a href=\"https://example.com/\" rel=\"nofollow ugc\">Example</a> <a href=\"/about\">About</a>"
+    };
+    [safe_javascript_link] (parser::Options { safe: true, ..Default::default() }) { "<#javascript:alert(1) //click//>" -> "<em>click</em>" };
+    [safe_hidden_scheme] (parser::Options { safe: true, ..Default::default() }) { "<#java&#115;cript:alert(1) click>" -> "click" };
+    [safe_image] (parser::Options { safe: true, ..Default::default() }) {
+        "<!data:image/svg+xml,x A picture> <!cat.png>" ->
+        "A picture <img src=\"cat.png\" loading=\"lazy\">"
+    };
+    [safe_media] (parser::Options { safe: true, ..Default::default() }) { "<@javascript:talk.mp4 A talk>" -> "A talk" };
+    [safe_image_size] (parser::Options { safe: true, base_dir: "test/fixtures".into(), ..Default::default() }) {
+        "<!image.png> <!../../../../dev/zero x>" ->
+        "<img src=\"image.png\" loading=\"lazy\"> <img src=\"../../../../dev/zero\" alt=\"x\" loading=\"lazy\">"
+    };
+    [safe_media_poster] (parser::Options { safe: true, ..Default::default() }) {
+        "<@talk.webm>{poster=javascript:alert(1)}" ->
+        "<video src=\"talk.webm\" controls=\"\"><a href=\"talk.webm\">talk.webm</a></video>"
+    };
+    [safe_raw_html] (parser::Options { safe: true, ..Default::default() }) { "<rawhtml>\n<script>alert(1)</script>\n</>\ntext" -> "text" };
+    [safe_include] (parser::Options { safe: true, ..Default::default() }) { "<rs include=../test/fixtures/include.rs>" -> "" };
+    [safe_attribute_escaping] (parser::Options { safe: true, ..Default::default() }) {
+        "<#https://example.com/\"onmouseover=\"alert(1) x>" ->
+        "<a href=\"https://example.com/&quot;onmouseover=&quot;alert(1)\" rel=\"nofollow ugc\">x</a>"
+    };
+    [safe_placeholders] (parser::Options { safe: true, ..Default::default() }) {
+        "hi \u{E003}script\u{E002}alert(1)\u{E003}/script\u{E002}" -> "hi scriptalert(1)/script"
+    };
+    [safe_placeholders_in_attribute] (parser::Options { safe: true, ..Default::default() }) {
+        "<#https://example.com/ x\u{E007} onmouseover=\u{E007}alert(1)>" ->
+        "<a href=\"https://example.com/\" rel=\"nofollow ugc\">x onmouseover=alert(1)</a>"
+    };
+}
@@ -0,0 +1,44 @@
+use crate::{parser, toc::TocLevels};
+
+parser_tests! {
+    [toc] {
+        "<toc>\n== A ==\n=== B ===\n=== C ===\n== D ==" ->
+        "<nav class=\"fxg-toc\"><ul><li><a href=\"#a\">A</a><ul><li><a href=\"#b\">B</a></li><li><a href=\"#c\">C</a></li></ul></li><li><a href=\"#d\">D</a></li></ul></nav><h2 id=\"a\">A</h2><h3 id=\"b\">B</h3><h3 id=\"c\">C</h3><h2 id=\"d\">D</h2>"
+    };
+    [toc_only_below] {
+        "== A ==\n<toc>\n== B ==" ->
+        "<h2 id=\"a\">A</h2><nav class=\"fxg-toc\"><ul><li><a href=\"#b\">B</a></li></ul></nav><h2 id=\"b\">B</h2>"
+    };
+    [toc_levels] {
+        "<toc min=2 max=2>\n= Title =\n== A ==\n=== B ===" ->
+        "<nav class=\"fxg-toc\"><ul><li><a href=\"#a\">A</a></li></ul></nav><h1 id=\"title\">Title</h1><h2 id=\"a\">A</h2><h3 id=\"b\">B</h3>"
+    };
+    [toc_skipped_level] {
+        "<toc>\n== A ==\n==== B ====\n=== C ===" ->
+        "<nav class=\"fxg-toc\"><ul><li><a href=\"#a\">A</a><ul><li><a href=\"#b\">B</a></li></ul><ul><li><a href=\"#c\">C</a></li></ul></li></ul></nav><h2 id=\"a\">A</h2><h4 id=\"b\">B</h4><h3 id=\"c\">C</h3>"
+    };
+    [toc_markup] {
+        "<toc>\n== //A// ==" ->
+        "<nav class=\"fxg-toc\"><ul><li><a href=\"#a\"><em>A</em></a></li></ul></nav><h2 id=\"a\"><em>A</em></h2>"
+    };
+    [toc_prepended_cross_reference] (parser::Options { toc: Some(TocLevels::default()), number_headings: true, ..Default::default() }) {
+        "== About <##b> ==\n== B ==" ->
+        "<nav class=\"fxg-toc\"><ul><li><a href=\"#about-b\"><span class=\"fxg-secnum\">1</span> About <a href=\"#b\">2</a></a></li><li><a href=\"#b\"><span class=\"fxg-secnum\">2</span> B</a></li></ul></nav><h2 id=\"about-b\"><span class=\"fxg-secnum\">1</span> About <a href=\"#b\">2</a></h2><h2 id=\"b\"><span class=\"fxg-secnum\">2</span> B</h2>"
+    };
+    [toc_unknown_option] {
+        "<toc depth=2>\n== A ==" ->
+        "<nav class=\"fxg-toc\"><ul><li><a href=\"#a\">A</a></li></ul></nav><h2 id=\"a\">A</h2>"
+    };
+    [toc_level_out_of_range] {
+        "<toc min=9 max=2>\n== A ==\n=== B ===" ->
+        "<nav class=\"fxg-toc\"><ul><li><a href=\"#a\">A</a></li></ul></nav><h2 id=\"a\">A</h2><h3 id=\"b\">B</h3>"
+    };
+    [toc_levels_reversed] {
+        "<toc min=4 max=2>\n== A ==\n=== B ===" ->
+        "<nav class=\"fxg-toc\"><ul><li><a href=\"#a\">A</a><ul><li><a href=\"#b\">B</a></li></ul></li></ul></nav><h2 id=\"a\">A</h2><h3 id=\"b\">B</h3>"
+    };
+    [toc_prepended] (parser::Options { toc: Some(TocLevels::default()), ..Default::default() }) {
+        "text\n== A ==" ->
+        "<nav class=\"fxg-toc\"><ul><li><a href=\"#a\">A</a></li></ul></nav>text <h2 id=\"a\">A</h2>"
+    };
+}
@@ -0,0 +1,27 @@
+use crate::parser;
+
+parser_tests! {
+    [smart_quotes] (parser::Options { smart_typography: true, ..Default::default() }) {
+        "\"Hello,\" she said, \"it's 'fine'.\"" ->
+        "“Hello,” she said, “it’s ‘fine’.”"
+    };
+    [smart_quotes_around_markup] (parser::Options { smart_typography: true, ..Default::default() }) {
+        "a \"//quote//\" and //\"another\"//" ->
+        "a “<em>quote</em>” and <em>“another”</em>"
+    };
+    [smart_dashes_ellipsis] (parser::Options { smart_typography: true, ..Default::default() }) { "1--2 --- wait..." -> "1–2 — wait…" };
+    [smart_german] (parser::Options { smart_typography: true, lang: Some("de-AT".to_string()), ..Default::default() }) { "\"Hallo\", 'ja'" -> "„Hallo“, ‚ja‘" };
+    [smart_french] (parser::Options { smart_typography: true, lang: Some("fr".to_string()), ..Default::default() }) {
+        "Il a dit : \"Bonjour\" ! Quoi? 10:30" ->
+        "Il a dit\u{A0}: «\u{A0}Bonjour\u{A0}»\u{202F}! Quoi\u{202F}? 10:30"
+    };
+    [smart_skips_code_and_urls] (parser::Options { smart_typography: true, ..Default::default() }) {
+        "<>\"code\" -- ...</> see https://example.com/a--b?q=\"x\"y" ->
+        "<code>\"code\" -- ...</code> see https://example.com/a--b?q=\"x\"y"
+    };
+    [smart_quote_after_url] (parser::Options { smart_typography: true, ..Default::default() }) {
+        "He said \"see http://x.com\" ok. <http://x.com> \"ok\"" ->
+        "He said “see http://x.com” ok. &lt;http://x.com&gt; “ok”"
+    };
+    [smart_disabled] { "\"quote\" -- ..." -> "\"quote\" -- ..." };
+}